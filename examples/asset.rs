@@ -0,0 +1,40 @@
+use bevy::{asset::AssetServerSettings, prelude::*, reflect::TypeUuid};
+use bevy_input_actionmap::*;
+use serde::{Deserialize, Serialize};
+
+fn main() {
+    App::new()
+        //hot reloading also needs bevy's `filesystem_watcher` feature
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ActionPlugin::<Action>::default())
+        .add_plugin(InputMapAssetPlugin::<Action>::default())
+        .add_startup_system(setup)
+        .add_system(run_commands)
+        .run();
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TypeUuid)]
+#[uuid = "0f3a6d4e-52b1-4c9e-8e0a-9d7c2b5f1e36"]
+enum Action {
+    Jump,
+    Fire,
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    //edit assets/default.inputmap.ron while this runs to change the bindings
+    let bindings: Handle<InputMapAsset<Action>> = asset_server.load("default.inputmap.ron");
+    commands.insert_resource(bindings);
+}
+
+fn run_commands(input: Res<InputMap<Action>>) {
+    if input.just_active(Action::Jump) {
+        println!("Jump");
+    }
+    if input.just_active(Action::Fire) {
+        println!("Fire");
+    }
+}
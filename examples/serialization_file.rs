@@ -21,22 +21,29 @@ enum Action {
     Right,
 }
 
-const PATH: &'static str = "examples/keybindings.ron";
+const PATH: &str = "examples/keybindings.ron";
+
+//bump the version and register a migration when renaming or removing actions
+fn save_format() -> SaveFormat {
+    SaveFormat::new(1)
+}
 
 fn setup(mut input: ResMut<InputMap<Action>>) {
+    create_default_keybindings(&mut input);
+    input.set_defaults();
     #[cfg(feature = "serialize")]
-    if let Err(_) = load_from_path(&mut input, PATH) {
-        {
-            println!("no keybind config found creating default setup"); //just to show the path it took
-            create_default_keybindings(&mut input);
+    match load_from_path(&mut input, PATH) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("no keybind config found saving default setup"); //just to show the path it took
             save_to_path(&input, PATH).unwrap()
         }
-    } else {
-        //if it loaded custom keybinds dont add new ones
-        println!("keybindings loaded from local file") //just to show the path it took
+        Err(e) => {
+            //keep the broken file around rather than overwriting the player's bindings
+            println!("keybind config could not be loaded, using defaults: {}", e)
+        }
+        //only the bindings the player changed are saved, defaults fill in the rest
+        Ok(()) => println!("keybindings loaded from local file"), //just to show the path it took
     }
-    #[cfg(not(feature = "serialize"))]
-    create_default_keybindings(&mut input);
 }
 
 fn create_default_keybindings(input: &mut ResMut<InputMap<Action>>) {
@@ -73,7 +80,8 @@ fn run_commands(input: Res<InputMap<Action>>) {
 }
 
 fn save_to_path(input: &InputMap<Action>, path: &str) -> std::io::Result<()> {
-    let contents = ron::ser::to_string_pretty(input, ron::ser::PrettyConfig::default())
+    let saved = save_format().save(&input.overrides());
+    let contents = ron::ser::to_string_pretty(&saved, ron::ser::PrettyConfig::default())
         .expect("There was an error making the string");
     std::fs::write(path, contents)?;
     Ok(())
@@ -81,8 +89,19 @@ fn save_to_path(input: &InputMap<Action>, path: &str) -> std::io::Result<()> {
 
 fn load_from_path(input: &mut InputMap<Action>, path: &str) -> std::io::Result<()> {
     let ron_string = std::fs::read_to_string(path)?;
-    let config = ron::from_str::<InputMap<Action>>(&ron_string)
-        .expect("Failed to get actions from ron string");
-    *input = config;
+    //files saved before versioning are a bare map of actions, migrated from version 0
+    let saved = ron::from_str::<VersionedBindings>(&ron_string)
+        .or_else(|e| {
+            ron::from_str::<LegacyBindings<Action>>(&ron_string)
+                .map(Into::into)
+                .map_err(|_| e)
+        })
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    //bindings naming keys or actions that don't exist are skipped instead of failing the load
+    let (overrides, rejected) = save_format().load::<Action>(saved);
+    for rejection in &rejected {
+        println!("skipping {}", rejection);
+    }
+    input.apply_overrides(&overrides);
     Ok(())
 }
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData};
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypeUuid, Uuid},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{Action, BindingOverrides, InputMap, Lenient};
+
+/// Bindings loaded from an `.inputmap.ron` file, in the same format as a serialized [`InputMap`].
+/// Loaded by the [`InputMapAssetPlugin`], which applies them to the `InputMap<T>` resource
+/// whenever they are loaded or modified.
+///
+/// Only RON files are loaded by the [`InputMapLoader`]. Bindings in other serde formats can be
+/// loaded by registering an [`AssetLoader`] that deserializes this type, though that skips the
+/// warnings about actions and bindings that can't be loaded.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent, bound(deserialize = "T: Deserialize<'de> + Eq + Hash"))]
+pub struct InputMapAsset<T> {
+    actions: HashMap<T, Action>,
+}
+
+impl<T> InputMapAsset<T>
+where
+    T: Hash + Eq,
+{
+    /// Iterates over the actions of the asset and their bindings.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &Action)> {
+        self.actions.iter()
+    }
+}
+
+/// Each action type gets its own asset type, so the UUID is derived from that of the action type
+impl<T> TypeUuid for InputMapAsset<T>
+where
+    T: TypeUuid,
+{
+    const TYPE_UUID: Uuid = {
+        const BASE: [u8; 16] = [
+            0x5c, 0x1e, 0x3a, 0x0b, 0x8f, 0x42, 0x4d, 0x6e, 0x9a, 0x27, 0x10, 0xd3, 0x6b, 0xe4,
+            0x58, 0x91,
+        ];
+        let action = T::TYPE_UUID;
+        let action = action.as_bytes();
+        let mut bytes = [0; 16];
+        let mut i = 0;
+        while i < 16 {
+            bytes[i] = BASE[i] ^ action[i];
+            i += 1;
+        }
+        Uuid::from_bytes(bytes)
+    };
+}
+
+/// Loads [`InputMapAsset`]s from `.inputmap.ron` files. Actions and bindings that can't be loaded,
+/// such as those naming keys that don't exist, are skipped with a warning.
+pub struct InputMapLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for InputMapLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> AssetLoader for InputMapLoader<T>
+where
+    T: TypeUuid + DeserializeOwned + Serialize + Hash + Eq + Debug + Send + Sync + 'static,
+{
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let loaded = ron::de::from_bytes::<Lenient<BindingOverrides<T>>>(bytes)?;
+            for rejection in &loaded.rejected {
+                warn!(
+                    "Skipping {} in {}",
+                    rejection,
+                    load_context.path().display()
+                );
+            }
+            load_context.set_default_asset(LoadedAsset::new(InputMapAsset {
+                actions: loaded.value.actions,
+            }));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["inputmap.ron"]
+    }
+}
+
+/// Registers [`InputMapAsset`]s and applies them to the `InputMap<T>` resource when loaded, and
+/// again whenever they are modified if the asset server watches for changes. The asset's bindings
+/// become the defaults of the map and replace those set up in code, while actions the player
+/// rebound since the asset was loaded or [`InputMap::set_defaults`] was called keep their bindings.
+///
+/// Must be added after the `AssetPlugin`. Keep the handle returned by the `AssetServer` around,
+/// since the asset is unloaded once it is dropped:
+///
+/// ```no_run
+/// use bevy::{prelude::*, reflect::TypeUuid};
+/// use bevy_input_actionmap::*;
+///
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, TypeUuid, serde::Serialize, serde::Deserialize)]
+/// #[uuid = "1b6a1b2e-3c8f-4a4c-a5c1-6f0e1f4f5a10"]
+/// enum Action {
+///     Jump,
+/// }
+///
+/// fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     let bindings: Handle<InputMapAsset<Action>> = asset_server.load("default.inputmap.ron");
+///     commands.insert_resource(bindings);
+/// }
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugin(ActionPlugin::<Action>::default())
+///     .add_plugin(InputMapAssetPlugin::<Action>::default())
+///     .add_startup_system(setup)
+///     .run();
+/// ```
+pub struct InputMapAssetPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for InputMapAssetPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Plugin for InputMapAssetPlugin<T>
+where
+    T: TypeUuid + DeserializeOwned + Serialize + Hash + Eq + Clone + Debug + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.add_asset::<InputMapAsset<T>>()
+            .init_asset_loader::<InputMapLoader<T>>()
+            .add_system_to_stage(CoreStage::First, apply_input_map_assets::<T>);
+    }
+}
+
+fn apply_input_map_assets<T>(
+    mut events: EventReader<AssetEvent<InputMapAsset<T>>>,
+    assets: Res<Assets<InputMapAsset<T>>>,
+    mut input_map: ResMut<InputMap<T>>,
+) where
+    T: TypeUuid + Hash + Eq + Clone + Send + Sync + 'static,
+{
+    for event in events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if let Some(asset) = assets.get(handle) {
+                // Without defaults, every binding set up in code would count as an override
+                let overrides = if input_map.defaults.is_empty() {
+                    BindingOverrides::default()
+                } else {
+                    input_map.overrides()
+                };
+                input_map.defaults = asset.actions.clone();
+                input_map.apply_overrides(&overrides);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_asset_uuid() {
+    #[derive(TypeUuid)]
+    #[uuid = "6d1c0e8a-2f4b-4e57-9a3d-7c1e5b0f2a48"]
+    struct Menu;
+    #[derive(TypeUuid)]
+    #[uuid = "a83f5c21-9e0d-4b6a-8f12-3d4c7e6b1a95"]
+    struct Game;
+
+    assert_ne!(
+        InputMapAsset::<Menu>::TYPE_UUID,
+        InputMapAsset::<Game>::TYPE_UUID
+    );
+    assert_ne!(InputMapAsset::<Menu>::TYPE_UUID, Menu::TYPE_UUID);
+}
+
+#[test]
+fn test_apply_assets() {
+    #[derive(Clone, Debug, Eq, Hash, PartialEq, TypeUuid, Serialize, Deserialize)]
+    #[uuid = "3f9b2c71-5d0e-4a8b-b6c4-2e1f7a9d0c53"]
+    enum Controls {
+        Jump,
+        Crouch,
+    }
+
+    let action = |key: KeyCode| Action {
+        bindings: vec![key.into()],
+        ..default()
+    };
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(bevy::input::InputPlugin)
+        .add_plugin(bevy::asset::AssetPlugin)
+        .add_plugin(crate::ActionPlugin::<Controls>::default())
+        .add_plugin(InputMapAssetPlugin::<Controls>::default());
+    app.world
+        .resource_mut::<InputMap<Controls>>()
+        .bind(Controls::Jump, KeyCode::Space);
+    let mut asset = InputMapAsset {
+        actions: HashMap::new(),
+    };
+    asset.actions.insert(Controls::Jump, action(KeyCode::W));
+    let handle = app
+        .world
+        .resource_mut::<Assets<InputMapAsset<Controls>>>()
+        .add(asset);
+    // Asset events are sent at the end of the frame, and applied in the next
+    app.update();
+    app.update();
+    let map = |app: &App| app.world.resource::<InputMap<Controls>>().actions.clone();
+    assert_eq!(map(&app)[&Controls::Jump], action(KeyCode::W));
+
+    // Rebound actions are kept when the asset is modified
+    app.world
+        .resource_mut::<InputMap<Controls>>()
+        .bind(Controls::Jump, KeyCode::Space);
+    let mut assets = app.world.resource_mut::<Assets<InputMapAsset<Controls>>>();
+    let asset = assets.get_mut(&handle).unwrap();
+    asset.actions.insert(Controls::Jump, action(KeyCode::Up));
+    asset.actions.insert(Controls::Crouch, action(KeyCode::C));
+    app.update();
+    app.update();
+    assert_eq!(
+        map(&app)[&Controls::Jump].bindings(),
+        [KeyCode::W.into(), KeyCode::Space.into()]
+    );
+    assert_eq!(map(&app)[&Controls::Crouch], action(KeyCode::C));
+}
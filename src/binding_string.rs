@@ -0,0 +1,595 @@
+//! A compact textual syntax for [`Binding`]s, eg. `"LControl+S"`, `"Gamepad:South"`,
+//! `"Mouse:Left"` or `"LeftStickX+ > 0.2"`.
+//!
+//! Inputs are joined with `+`. Keys are named as their [`KeyCode`] variant, and modifiers matching
+//! either side of the keyboard as their [`Modifier`] variant, eg. `"Primary+S"`. Physical keys are
+//! given as their [`ScanCode`] prefixed with `Scan:`, eg. `"Scan:17"`, and typed characters last
+//! prefixed with `Char:`, eg. `"Char:?"` or `"Control+Char:+"`. Mouse buttons are
+//! prefixed with `Mouse:` and gamepad buttons with `Gamepad:`. Gamepad axis directions are named
+//! after the axis followed by `+` or `-`, and a deadzone may follow the inputs after `>`.
+//!
+//! With the `serialize` feature, saved [`InputMap`](crate::InputMap)s accept bindings written as
+//! these strings as well as in their struct form. This module can be used with
+//! `#[serde(with = "...")]` to save bindings as strings, keeping config files short enough to edit
+//! by hand:
+//!
+//! ```
+//! # #[cfg(feature = "serialize")]
+//! # {
+//! # use bevy_input_actionmap::Binding;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(with = "bevy_input_actionmap::binding_string::vec")]
+//!     jump: Vec<Binding>,
+//! }
+//!
+//! let config = ron::from_str::<Config>(r#"(jump: ["Space", "Gamepad:South"])"#).unwrap();
+//! assert_eq!(config.jump.len(), 2);
+//! # }
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::*;
+
+use crate::{modifier::is_modifier_key, Binding, GamepadAxisDirection, Modifier, ScanCode};
+
+/// Error returned when parsing a [`Binding`] from a string fails.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseBindingError {
+    /// A key, button or axis direction that doesn't exist.
+    UnknownInput(String),
+    /// A deadzone that isn't a number between 0 and 1.
+    InvalidDeadzone(String),
+}
+
+impl fmt::Display for ParseBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBindingError::UnknownInput(name) => write!(f, "unknown input {}", name),
+            ParseBindingError::InvalidDeadzone(deadzone) => {
+                write!(
+                    f,
+                    "invalid deadzone {}, expected a number between 0 and 1",
+                    deadzone
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBindingError {}
+
+impl FromStr for Binding {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The deadzone follows the last `>`, which may otherwise be a typed character
+        let (inputs, deadzone) = match s.rsplit_once('>') {
+            Some((inputs, deadzone)) => {
+                let deadzone = deadzone.trim();
+                match deadzone.parse::<f32>() {
+                    Ok(v) if (0. ..=1.).contains(&v) => (inputs, v),
+                    Err(_) if s.contains("Char:") => (s, 0.),
+                    _ => return Err(ParseBindingError::InvalidDeadzone(deadzone.to_string())),
+                }
+            }
+            None => (s, 0.),
+        };
+        let mut binding = parse_inputs(inputs)?;
+        binding.deadzone = deadzone;
+        Ok(binding)
+    }
+}
+
+/// Parses the inputs of a binding string, without its deadzone
+fn parse_inputs(inputs: &str) -> Result<Binding, ParseBindingError> {
+    // A character comes last, as it may be `+` or `>`
+    if let Some((inputs, character)) = inputs.split_once("Char:") {
+        let mut chars = character.trim().chars();
+        let character = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => {
+                return Err(ParseBindingError::UnknownInput(format!(
+                    "Char:{}",
+                    character
+                )))
+            }
+        };
+        let inputs = inputs.trim();
+        let mut binding = if inputs.is_empty() {
+            Binding::default()
+        } else {
+            match inputs.strip_suffix('+') {
+                Some(inputs) if !inputs.trim().is_empty() => parse_inputs(inputs)?,
+                _ => return Err(ParseBindingError::UnknownInput(inputs.to_string())),
+            }
+        };
+        binding.character = Some(character);
+        return Ok(binding);
+    }
+    let mut binding = Binding::default();
+    if inputs.trim().is_empty() {
+        return Ok(binding);
+    }
+    let mut tokens = inputs.split('+').map(str::trim);
+    while let Some(token) = tokens.next() {
+        if let Some(name) = token.strip_prefix("Scan:") {
+            let scan_code = name
+                .trim()
+                .parse()
+                .map_err(|_| ParseBindingError::UnknownInput(token.to_string()))?;
+            binding.scan_codes.insert(ScanCode(scan_code));
+        } else if let Some(name) = token.strip_prefix("Mouse:") {
+            binding
+                .mouse_buttons
+                .insert(parse_mouse_button(name.trim())?);
+        } else if let Some(name) = token.strip_prefix("Gamepad:") {
+            let name = name.trim();
+            let button = GAMEPAD_BUTTONS
+                .iter()
+                .find(|v| format!("{:?}", v) == name)
+                .ok_or_else(|| ParseBindingError::UnknownInput(token.to_string()))?;
+            binding.gamepad_buttons.insert(*button);
+        } else if let Some(name) = token.strip_suffix('-') {
+            binding
+                .gamepad_axis_directions
+                .insert(parse_axis_direction(name.trim(), '-')?);
+        } else if AXES.iter().any(|(_, v)| *v == token) {
+            // The `+` of a positive direction splits into an empty token
+            if tokens.next() != Some("") {
+                return Err(ParseBindingError::UnknownInput(token.to_string()));
+            }
+            binding
+                .gamepad_axis_directions
+                .insert(parse_axis_direction(token, '+')?);
+        } else if let Some(modifier) = MODIFIERS.iter().find(|v| format!("{:?}", v) == token) {
+            binding.modifiers.insert(*modifier);
+        } else {
+            let key = KEYS
+                .iter()
+                .find(|v| format!("{:?}", v) == token)
+                .ok_or_else(|| ParseBindingError::UnknownInput(token.to_string()))?;
+            binding.keys.insert(*key);
+        }
+    }
+    Ok(binding)
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Modifiers first, as in "LControl+S"
+        let mut keys = self
+            .modifiers
+            .iter()
+            .map(|v| (0, format!("{:?}", v)))
+            .chain(
+                self.keys
+                    .iter()
+                    .map(|v| (if is_modifier_key(*v) { 1 } else { 2 }, format!("{:?}", v))),
+            )
+            .collect::<Vec<_>>();
+        keys.sort();
+        let mut scan_codes = self.scan_codes.iter().collect::<Vec<_>>();
+        scan_codes.sort();
+        let mut mouse_buttons = self
+            .mouse_buttons
+            .iter()
+            .map(|v| match v {
+                MouseButton::Other(v) => format!("Mouse:{}", v),
+                v => format!("Mouse:{:?}", v),
+            })
+            .collect::<Vec<_>>();
+        mouse_buttons.sort();
+        let mut gamepad_buttons = self
+            .gamepad_buttons
+            .iter()
+            .map(|v| format!("Gamepad:{:?}", v))
+            .collect::<Vec<_>>();
+        gamepad_buttons.sort();
+        let mut axes = self
+            .gamepad_axis_directions
+            .iter()
+            .map(|v| {
+                let (axis, sign) = axis_name(*v);
+                format!("{}{}", axis, sign)
+            })
+            .collect::<Vec<_>>();
+        axes.sort();
+        let inputs = keys
+            .into_iter()
+            .map(|(_, v)| v)
+            .chain(scan_codes.into_iter().map(|v| format!("Scan:{}", v.0)))
+            .chain(mouse_buttons)
+            .chain(gamepad_buttons)
+            .chain(axes)
+            .collect::<Vec<_>>();
+        f.write_str(&inputs.join("+"))?;
+        if let Some(character) = self.character {
+            if !inputs.is_empty() {
+                f.write_str("+")?;
+            }
+            write!(f, "Char:{}", character)?;
+        }
+        if self.deadzone != 0. {
+            write!(f, " > {}", self.deadzone)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_mouse_button(name: &str) -> Result<MouseButton, ParseBindingError> {
+    match name {
+        "Left" => Ok(MouseButton::Left),
+        "Right" => Ok(MouseButton::Right),
+        "Middle" => Ok(MouseButton::Middle),
+        _ => name
+            .parse()
+            .map(MouseButton::Other)
+            .map_err(|_| ParseBindingError::UnknownInput(format!("Mouse:{}", name))),
+    }
+}
+
+fn parse_axis_direction(axis: &str, sign: char) -> Result<GamepadAxisDirection, ParseBindingError> {
+    AXES.iter()
+        .find(|(v, name)| *name == axis && axis_name(*v).1 == sign)
+        .map(|(v, _)| *v)
+        .ok_or_else(|| ParseBindingError::UnknownInput(format!("{}{}", axis, sign)))
+}
+
+fn axis_name(direction: GamepadAxisDirection) -> (&'static str, char) {
+    use GamepadAxisDirection::*;
+    match direction {
+        LeftStickXPositive => ("LeftStickX", '+'),
+        LeftStickXNegative => ("LeftStickX", '-'),
+        LeftStickYPositive => ("LeftStickY", '+'),
+        LeftStickYNegative => ("LeftStickY", '-'),
+        RightStickXPositive => ("RightStickX", '+'),
+        RightStickXNegative => ("RightStickX", '-'),
+        RightStickYPositive => ("RightStickY", '+'),
+        RightStickYNegative => ("RightStickY", '-'),
+        DPadXPositive => ("DPadX", '+'),
+        DPadXNegative => ("DPadX", '-'),
+        DPadYPositive => ("DPadY", '+'),
+        DPadYNegative => ("DPadY", '-'),
+    }
+}
+
+const MODIFIERS: [Modifier; 5] = [
+    Modifier::Shift,
+    Modifier::Control,
+    Modifier::Alt,
+    Modifier::Super,
+    Modifier::Primary,
+];
+
+const AXES: [(GamepadAxisDirection, &str); 12] = [
+    (GamepadAxisDirection::LeftStickXPositive, "LeftStickX"),
+    (GamepadAxisDirection::LeftStickXNegative, "LeftStickX"),
+    (GamepadAxisDirection::LeftStickYPositive, "LeftStickY"),
+    (GamepadAxisDirection::LeftStickYNegative, "LeftStickY"),
+    (GamepadAxisDirection::RightStickXPositive, "RightStickX"),
+    (GamepadAxisDirection::RightStickXNegative, "RightStickX"),
+    (GamepadAxisDirection::RightStickYPositive, "RightStickY"),
+    (GamepadAxisDirection::RightStickYNegative, "RightStickY"),
+    (GamepadAxisDirection::DPadXPositive, "DPadX"),
+    (GamepadAxisDirection::DPadXNegative, "DPadX"),
+    (GamepadAxisDirection::DPadYPositive, "DPadY"),
+    (GamepadAxisDirection::DPadYNegative, "DPadY"),
+];
+
+const GAMEPAD_BUTTONS: [GamepadButtonType; 19] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+    GamepadButtonType::C,
+    GamepadButtonType::Z,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::Mode,
+    GamepadButtonType::LeftThumb,
+    GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+];
+
+const KEYS: [KeyCode; 163] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Escape,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::F13,
+    KeyCode::F14,
+    KeyCode::F15,
+    KeyCode::F16,
+    KeyCode::F17,
+    KeyCode::F18,
+    KeyCode::F19,
+    KeyCode::F20,
+    KeyCode::F21,
+    KeyCode::F22,
+    KeyCode::F23,
+    KeyCode::F24,
+    KeyCode::Snapshot,
+    KeyCode::Scroll,
+    KeyCode::Pause,
+    KeyCode::Insert,
+    KeyCode::Home,
+    KeyCode::Delete,
+    KeyCode::End,
+    KeyCode::PageDown,
+    KeyCode::PageUp,
+    KeyCode::Left,
+    KeyCode::Up,
+    KeyCode::Right,
+    KeyCode::Down,
+    KeyCode::Back,
+    KeyCode::Return,
+    KeyCode::Space,
+    KeyCode::Compose,
+    KeyCode::Caret,
+    KeyCode::Numlock,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::AbntC1,
+    KeyCode::AbntC2,
+    KeyCode::NumpadAdd,
+    KeyCode::Apostrophe,
+    KeyCode::Apps,
+    KeyCode::Asterisk,
+    KeyCode::Plus,
+    KeyCode::At,
+    KeyCode::Ax,
+    KeyCode::Backslash,
+    KeyCode::Calculator,
+    KeyCode::Capital,
+    KeyCode::Colon,
+    KeyCode::Comma,
+    KeyCode::Convert,
+    KeyCode::NumpadDecimal,
+    KeyCode::NumpadDivide,
+    KeyCode::Equals,
+    KeyCode::Grave,
+    KeyCode::Kana,
+    KeyCode::Kanji,
+    KeyCode::LAlt,
+    KeyCode::LBracket,
+    KeyCode::LControl,
+    KeyCode::LShift,
+    KeyCode::LWin,
+    KeyCode::Mail,
+    KeyCode::MediaSelect,
+    KeyCode::MediaStop,
+    KeyCode::Minus,
+    KeyCode::NumpadMultiply,
+    KeyCode::Mute,
+    KeyCode::MyComputer,
+    KeyCode::NavigateForward,
+    KeyCode::NavigateBackward,
+    KeyCode::NextTrack,
+    KeyCode::NoConvert,
+    KeyCode::NumpadComma,
+    KeyCode::NumpadEnter,
+    KeyCode::NumpadEquals,
+    KeyCode::Oem102,
+    KeyCode::Period,
+    KeyCode::PlayPause,
+    KeyCode::Power,
+    KeyCode::PrevTrack,
+    KeyCode::RAlt,
+    KeyCode::RBracket,
+    KeyCode::RControl,
+    KeyCode::RShift,
+    KeyCode::RWin,
+    KeyCode::Semicolon,
+    KeyCode::Slash,
+    KeyCode::Sleep,
+    KeyCode::Stop,
+    KeyCode::NumpadSubtract,
+    KeyCode::Sysrq,
+    KeyCode::Tab,
+    KeyCode::Underline,
+    KeyCode::Unlabeled,
+    KeyCode::VolumeDown,
+    KeyCode::VolumeUp,
+    KeyCode::Wake,
+    KeyCode::WebBack,
+    KeyCode::WebFavorites,
+    KeyCode::WebForward,
+    KeyCode::WebHome,
+    KeyCode::WebRefresh,
+    KeyCode::WebSearch,
+    KeyCode::WebStop,
+    KeyCode::Yen,
+    KeyCode::Copy,
+    KeyCode::Paste,
+    KeyCode::Cut,
+];
+
+/// Serializes a [`Binding`] as a string, for use with `#[serde(with = "...")]`.
+#[cfg(feature = "serialize")]
+pub fn serialize<S: serde::Serializer>(
+    binding: &Binding,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(binding)
+}
+
+/// Deserializes a [`Binding`] from a string, for use with `#[serde(with = "...")]`. Bindings
+/// saved in their struct form by earlier versions are also accepted from human-readable formats.
+#[cfg(feature = "serialize")]
+pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Binding, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(BindingVisitor)
+    } else {
+        deserializer.deserialize_str(BindingVisitor)
+    }
+}
+
+#[cfg(feature = "serialize")]
+struct BindingVisitor;
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::de::Visitor<'de> for BindingVisitor {
+    type Value = Binding;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a binding string such as \"LControl+S\"")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        serde::Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+    }
+}
+
+/// Serializes a list of [`Binding`]s as strings, for use with `#[serde(with = "...")]`.
+#[cfg(feature = "serialize")]
+pub mod vec {
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    use crate::Binding;
+
+    pub fn serialize<S: Serializer>(
+        bindings: &[Binding],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(bindings.len()))?;
+        for binding in bindings {
+            seq.serialize_element(&binding.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Binding>, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(transparent)]
+        struct Element(#[serde(with = "super")] Binding);
+
+        Ok(Vec::<Element>::deserialize(deserializer)?
+            .into_iter()
+            .map(|v| v.0)
+            .collect())
+    }
+}
+
+#[test]
+fn test_binding_string() {
+    let binding = Binding::from(vec![KeyCode::S, KeyCode::LControl]);
+    assert_eq!(binding.to_string(), "LControl+S");
+    assert_eq!("LControl + S".parse(), Ok(binding));
+    let binding = Binding::from(KeyCode::S)
+        .with_modifier(Modifier::Primary)
+        .with_modifier(Modifier::Shift);
+    assert_eq!(binding.to_string(), "Primary+Shift+S");
+    assert_eq!(binding.to_string().parse(), Ok(binding));
+    let binding = Binding::from(ScanCode(17)).with_modifier(Modifier::Shift);
+    assert_eq!(binding.to_string(), "Shift+Scan:17");
+    assert_eq!(binding.to_string().parse(), Ok(binding));
+    let binding = "Gamepad:South+Mouse:4+LeftStickX++DPadY- > 0.2"
+        .parse::<Binding>()
+        .unwrap();
+    assert!(binding
+        .gamepad_buttons()
+        .contains(&GamepadButtonType::South));
+    assert!(binding.mouse_buttons().contains(&MouseButton::Other(4)));
+    assert_eq!(binding.gamepad_axis_directions().len(), 2);
+    assert_eq!(binding.deadzone(), 0.2);
+    assert_eq!(
+        binding.to_string(),
+        "Mouse:4+Gamepad:South+DPadY-+LeftStickX+ > 0.2"
+    );
+    assert_eq!(binding.to_string().parse(), Ok(binding));
+    assert_eq!("Char:+".parse(), Ok(Binding::from('+')));
+    assert_eq!(Binding::from('+').to_string(), "Char:+");
+    let binding = Binding::from('+').with_modifier(Modifier::Control);
+    assert_eq!(binding.to_string(), "Control+Char:+");
+    assert_eq!("Control + Char:+".parse(), Ok(binding));
+    let binding = "Control+Char:+ > 0.5".parse::<Binding>().unwrap();
+    assert_eq!((binding.character(), binding.deadzone()), (Some('+'), 0.5));
+    assert_eq!(binding.to_string(), "Control+Char:+ > 0.5");
+    let binding = "Char:> > 0.2".parse::<Binding>().unwrap();
+    assert_eq!((binding.character(), binding.deadzone()), (Some('>'), 0.2));
+    assert_eq!(binding.to_string().parse(), Ok(binding));
+    assert_eq!("Char:>".parse(), Ok(Binding::from('>')));
+    assert_eq!(
+        "Char:a+S".parse::<Binding>(),
+        Err(ParseBindingError::UnknownInput("Char:a+S".to_string()))
+    );
+    assert_eq!(
+        "Spacebar".parse::<Binding>(),
+        Err(ParseBindingError::UnknownInput("Spacebar".to_string()))
+    );
+    assert_eq!(
+        "Space > 2".parse::<Binding>(),
+        Err(ParseBindingError::InvalidDeadzone("2".to_string()))
+    );
+}
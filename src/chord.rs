@@ -0,0 +1,213 @@
+use std::{hash::Hash, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{modifier::is_modifier_key, Binding, InputMap, ScanCode};
+
+/// Decides whether a keyboard chord triggers depending on the order its keys were pressed in, eg.
+/// whether holding `S` then pressing `Control` triggers `Control+S`. Only bindings with both
+/// modifiers and other keys are affected. Set with [`InputMap::set_chord_order`] or
+/// [`ActionPlugin::with_chord_order`].
+///
+/// Whatever the order, a chord stops triggering as soon as any of its keys is released.
+///
+/// [`ActionPlugin::with_chord_order`]: crate::ActionPlugin::with_chord_order
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ChordOrder {
+    /// The chord triggers once all of its keys are held. Holding `S` then pressing `Control`
+    /// starts `Control+S`, canceling the action bound to `S` as it is more specific.
+    #[default]
+    AnyOrder,
+    /// The chord only triggers if its modifiers were held before its other keys, or pressed in the
+    /// same frame. Holding `S` then pressing `Control` keeps the action bound to `S` active, and
+    /// `Control+S` only starts once `S` is pressed again.
+    ModifiersFirst,
+    /// As with [`ChordOrder::ModifiersFirst`], except that the other keys may be pressed up to the
+    /// given time before the modifiers, to forgive sloppy presses.
+    Within(Duration),
+}
+
+impl<T> InputMap<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Sets whether keyboard chords trigger depending on the order their keys were pressed in.
+    pub fn set_chord_order(&mut self, order: ChordOrder) -> &mut Self {
+        self.chord_order = order;
+        self
+    }
+
+    /// Returns whether keyboard chords trigger depending on the order their keys were pressed in.
+    pub fn chord_order(&self) -> ChordOrder {
+        self.chord_order
+    }
+
+    /// Records when the held keys and scan codes were pressed. Keys pressed since the last update
+    /// are stamped with the time of that update, so that those pressed in the same frame count as
+    /// simultaneous, and the time between presses in different frames is that between the frames.
+    pub(crate) fn track_key_presses(
+        &mut self,
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+    ) {
+        let now = self.last_update;
+        self.key_presses.retain(|k, _| input.pressed(*k));
+        for key in input.get_just_pressed() {
+            self.key_presses.insert(*key, now);
+        }
+        for key in input.get_pressed() {
+            self.key_presses.entry(*key).or_insert(now);
+        }
+        self.scan_code_presses.retain(|k, _| scan_codes.pressed(*k));
+        for scan_code in scan_codes.get_just_pressed() {
+            self.scan_code_presses.insert(*scan_code, now);
+        }
+        for scan_code in scan_codes.get_pressed() {
+            self.scan_code_presses.entry(*scan_code).or_insert(now);
+        }
+    }
+
+    /// Returns whether the keys of a held binding were pressed in the order required by the
+    /// [`ChordOrder`]
+    pub(crate) fn in_chord_order(&self, binding: &Binding) -> bool {
+        let window = match self.chord_order {
+            ChordOrder::AnyOrder => return true,
+            ChordOrder::ModifiersFirst => Duration::ZERO,
+            ChordOrder::Within(window) => window,
+        };
+        // A modifier is held from when the first of its held keys was pressed
+        let modifiers = binding
+            .modifiers
+            .iter()
+            .filter_map(|v| {
+                v.keys()
+                    .iter()
+                    .filter_map(|k| self.key_presses.get(k))
+                    .min()
+                    .copied()
+            })
+            .chain(
+                binding
+                    .keys
+                    .iter()
+                    .filter(|k| is_modifier_key(**k))
+                    .filter_map(|k| self.key_presses.get(k).copied()),
+            )
+            .max();
+        let others = binding
+            .keys
+            .iter()
+            .filter(|k| !is_modifier_key(**k))
+            .filter_map(|k| self.key_presses.get(k).copied())
+            .chain(
+                binding
+                    .scan_codes
+                    .iter()
+                    .filter_map(|k| self.scan_code_presses.get(k).copied()),
+            )
+            .min();
+        match (modifiers, others) {
+            (Some(modifiers), Some(others)) => others + window >= modifiers,
+            _ => true,
+        }
+    }
+}
+
+#[test]
+fn test_chord_order() {
+    use crate::{ActionEvent, Modifier};
+
+    fn update(map: &mut InputMap<String>, input: &mut Input<KeyCode>, now: u64) {
+        map.state.clear_just_active_inactive();
+        map.update_keys(input, &default());
+        map.update_active(input, &default(), Duration::from_millis(now));
+        input.clear();
+    }
+
+    let save = Binding::from(KeyCode::S).with_modifier(Modifier::Control);
+    for order in [
+        ChordOrder::AnyOrder,
+        ChordOrder::ModifiersFirst,
+        ChordOrder::Within(Duration::from_millis(100)),
+    ] {
+        // Control then S triggers the chord whatever the order
+        let mut map = InputMap::<String>::default();
+        map.bind("save", save.clone())
+            .bind("back", KeyCode::S)
+            .set_chord_order(order);
+        let mut input = Input::<KeyCode>::default();
+        input.press(KeyCode::LControl);
+        update(&mut map, &mut input, 0);
+        update(&mut map, &mut input, 50);
+        input.press(KeyCode::S);
+        update(&mut map, &mut input, 100);
+        assert!(map.just_active("save"), "{:?}", order);
+        assert!(!map.active("back"), "{:?}", order);
+
+        // Releasing a key of the chord deactivates it, and the action bound to the keys still held
+        // becomes active without being just active
+        input.release(KeyCode::LControl);
+        update(&mut map, &mut input, 150);
+        assert!(map.just_inactive("save"), "{:?}", order);
+        assert!(
+            map.active("back") && !map.just_active("back"),
+            "{:?}",
+            order
+        );
+
+        // S then Control, in a later frame
+        let mut map = InputMap::<String>::default();
+        map.bind("save", save.clone())
+            .bind("back", KeyCode::S)
+            .set_chord_order(order);
+        let mut input = Input::<KeyCode>::default();
+        update(&mut map, &mut input, 0);
+        input.press(KeyCode::S);
+        update(&mut map, &mut input, 50);
+        assert!(map.just_active("back"), "{:?}", order);
+        input.press(KeyCode::LControl);
+        update(&mut map, &mut input, 100);
+        match order {
+            ChordOrder::ModifiersFirst => {
+                assert!(!map.active("save"));
+                assert!(map.active("back"));
+                assert!(!map.events.iter().any(|v| matches!(
+                    v,
+                    ActionEvent::Canceled { .. } | ActionEvent::Started { .. }
+                )));
+                // Pressing S again with Control held starts the chord
+                input.release(KeyCode::S);
+                update(&mut map, &mut input, 150);
+                assert!(map.just_inactive("back"));
+                input.press(KeyCode::S);
+                update(&mut map, &mut input, 200);
+                assert!(map.just_active("save"));
+                assert!(!map.active("back"));
+            }
+            _ => {
+                assert!(map.just_active("save"), "{:?}", order);
+                assert!(map.just_inactive("back"), "{:?}", order);
+                assert!(map.events.iter().any(|v| matches!(
+                    v,
+                    ActionEvent::Canceled { action, .. } if action == "back"
+                )));
+            }
+        }
+    }
+
+    // S then Control after the window
+    let mut map = InputMap::<String>::default();
+    map.bind("save", save)
+        .bind("back", KeyCode::S)
+        .set_chord_order(ChordOrder::Within(Duration::from_millis(100)));
+    let mut input = Input::<KeyCode>::default();
+    update(&mut map, &mut input, 0);
+    input.press(KeyCode::S);
+    for now in (50..=200).step_by(50) {
+        update(&mut map, &mut input, now);
+    }
+    input.press(KeyCode::LControl);
+    update(&mut map, &mut input, 250);
+    assert!(!map.active("save"));
+    assert!(map.active("back"));
+}
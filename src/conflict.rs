@@ -0,0 +1,418 @@
+use std::{collections::HashMap, fmt, hash::Hash, sync::Arc};
+
+use crate::{Binding, InputMap};
+
+/// Decides which of the bindings held at once trigger their actions. Set with
+/// [`InputMap::set_conflict_strategy`] or [`ActionPlugin::with_conflict_strategy`].
+///
+/// [`ActionPlugin::with_conflict_strategy`]: crate::ActionPlugin::with_conflict_strategy
+#[derive(Default)]
+pub enum ConflictStrategy<T> {
+    /// A binding suppresses those of other actions whose inputs it shares, unless they use all of
+    /// its inputs, so that holding `Control+S` triggers "save" but not "back" bound to `S`.
+    #[default]
+    MostSpecific,
+    /// Every held binding triggers its action.
+    AllFire,
+    /// A binding suppresses those of other actions with a lower [`InputMap::priority`] whose inputs
+    /// it shares. Bindings of actions with the same priority conflict as with
+    /// [`ConflictStrategy::MostSpecific`].
+    Priority,
+    /// Defers to an application-provided resolver.
+    Custom(Arc<dyn ConflictResolver<T>>),
+}
+
+impl<T> Clone for ConflictStrategy<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::MostSpecific => Self::MostSpecific,
+            Self::AllFire => Self::AllFire,
+            Self::Priority => Self::Priority,
+            Self::Custom(resolver) => Self::Custom(resolver.clone()),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ConflictStrategy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MostSpecific => f.write_str("MostSpecific"),
+            Self::AllFire => f.write_str("AllFire"),
+            Self::Priority => f.write_str("Priority"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Resolves conflicts between bindings held at once for [`ConflictStrategy::Custom`]. Actions
+/// allowed to share inputs with [`InputMap::allow_shared_inputs`] are never compared.
+///
+/// Implemented for closures taking the same arguments as [`ConflictResolver::suppresses`].
+pub trait ConflictResolver<T>: Send + Sync {
+    /// Returns whether `binding` of `action` keeps `other_binding` of `other_action` from
+    /// triggering while both are held.
+    fn suppresses(
+        &self,
+        action: &T,
+        binding: &Binding,
+        other_action: &T,
+        other_binding: &Binding,
+    ) -> bool;
+}
+
+impl<T, F> ConflictResolver<T> for F
+where
+    F: Fn(&T, &Binding, &T, &Binding) -> bool + Send + Sync,
+{
+    fn suppresses(
+        &self,
+        action: &T,
+        binding: &Binding,
+        other_action: &T,
+        other_binding: &Binding,
+    ) -> bool {
+        self(action, binding, other_action, other_binding)
+    }
+}
+
+/// Describes how two overlapping bindings interact when their inputs are held.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ConflictKind {
+    /// Both bindings use the same inputs, so both actions trigger together.
+    Identical,
+    /// The first binding contains every input of the second, so holding it triggers only the first
+    /// action.
+    Shadows,
+    /// The bindings share some inputs but neither contains the other, so holding both triggers
+    /// neither action.
+    Partial,
+}
+
+/// A pair of bindings of different actions that share inputs, as reported by
+/// [`InputMap::conflicts`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindingConflict<T> {
+    pub action: T,
+    pub binding: Binding,
+    pub other_action: T,
+    pub other_binding: Binding,
+    pub kind: ConflictKind,
+}
+
+impl<T> BindingConflict<T> {
+    /// Classifies the overlap between two bindings using the same rules applied when resolving
+    /// conflicts at runtime, ordering them so the more specific binding comes first.
+    fn new(action: T, binding: Binding, other_action: T, other_binding: Binding) -> Option<Self> {
+        let overlap = binding.overlap(&other_binding);
+        if overlap == 0 {
+            return None;
+        }
+        let (weight, other_weight) = (binding.weight(), other_binding.weight());
+        let conflict = if overlap == weight && overlap == other_weight {
+            Self {
+                action,
+                binding,
+                other_action,
+                other_binding,
+                kind: ConflictKind::Identical,
+            }
+        } else if overlap == other_weight {
+            Self {
+                action,
+                binding,
+                other_action,
+                other_binding,
+                kind: ConflictKind::Shadows,
+            }
+        } else if overlap == weight {
+            Self {
+                action: other_action,
+                binding: other_binding,
+                other_action: action,
+                other_binding: binding,
+                kind: ConflictKind::Shadows,
+            }
+        } else {
+            Self {
+                action,
+                binding,
+                other_action,
+                other_binding,
+                kind: ConflictKind::Partial,
+            }
+        };
+        Some(conflict)
+    }
+}
+
+impl<T> InputMap<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Sets how conflicts between bindings held at once are resolved.
+    pub fn set_conflict_strategy(&mut self, strategy: ConflictStrategy<T>) -> &mut Self {
+        self.conflict_strategy = strategy;
+        self
+    }
+
+    /// Returns how conflicts between bindings held at once are resolved.
+    pub fn conflict_strategy(&self) -> &ConflictStrategy<T> {
+        &self.conflict_strategy
+    }
+
+    /// Sets the priority of an action for [`ConflictStrategy::Priority`]. Priorities are saved
+    /// with the action's bindings, and default to 0.
+    pub fn set_priority<K: Into<T>>(&mut self, action: K, priority: i32) -> &mut Self {
+        self.actions.entry(action.into()).or_default().priority = priority;
+        self
+    }
+
+    /// Returns the priority of an action for [`ConflictStrategy::Priority`].
+    pub fn priority<K: Into<T>>(&self, action: K) -> i32 {
+        self.actions
+            .get(&action.into())
+            .map(|v| v.priority)
+            .unwrap_or_default()
+    }
+
+    /// Returns whether `binding` of `action` keeps `other_binding` of `other_action` from
+    /// triggering while both are held, according to the conflict strategy.
+    pub(crate) fn suppresses(
+        &self,
+        action: &T,
+        binding: &Binding,
+        other_action: &T,
+        other_binding: &Binding,
+    ) -> bool {
+        let most_specific = || {
+            let overlap = binding.overlap(other_binding);
+            overlap != 0 && overlap < binding.weight()
+        };
+        match &self.conflict_strategy {
+            ConflictStrategy::MostSpecific => most_specific(),
+            ConflictStrategy::AllFire => false,
+            ConflictStrategy::Priority => {
+                let priority = |k| self.actions.get(k).map(|v| v.priority).unwrap_or_default();
+                let (priority, other_priority) = (priority(action), priority(other_action));
+                if priority == other_priority {
+                    most_specific()
+                } else {
+                    priority > other_priority && binding.overlap(other_binding) != 0
+                }
+            }
+            ConflictStrategy::Custom(resolver) => {
+                resolver.suppresses(action, binding, other_action, other_binding)
+            }
+        }
+    }
+
+    /// Splits the indices of the held bindings into groups that may conflict with one another,
+    /// leaving out those that can't conflict with any other.
+    pub(crate) fn group_conflicting(&mut self, held: &[(T, Binding, f32)]) -> Vec<Vec<usize>> {
+        // Custom resolvers may suppress bindings that don't share inputs
+        if matches!(self.conflict_strategy, ConflictStrategy::Custom(_)) {
+            return vec![(0..held.len()).collect()];
+        }
+        let groups = self.conflict_groups();
+        let mut grouped = HashMap::<usize, Vec<usize>>::new();
+        for (i, (action, _, _)) in held.iter().enumerate() {
+            if let Some(group) = groups.get(action) {
+                grouped.entry(*group).or_default().push(i);
+            }
+        }
+        grouped.into_values().filter(|v| v.len() > 1).collect()
+    }
+
+    /// Returns the group of each action whose bindings share inputs with those of another action,
+    /// directly or through other actions. Groups are computed again after bindings change.
+    fn conflict_groups(&mut self) -> &HashMap<T, usize> {
+        let actions = &self.actions;
+        self.conflict_groups.get_or_insert_with(|| {
+            let actions = actions.iter().collect::<Vec<_>>();
+            let mut parents = (0..actions.len()).collect::<Vec<_>>();
+            for (i, (_, action)) in actions.iter().enumerate() {
+                for (j, (_, other)) in actions.iter().enumerate().skip(i + 1) {
+                    let overlaps = action.bindings.iter().any(|binding| {
+                        other
+                            .bindings
+                            .iter()
+                            .any(|other| binding.overlap(other) != 0)
+                    });
+                    if overlaps {
+                        let (i, j) = (root(&mut parents, i), root(&mut parents, j));
+                        parents[j] = i;
+                    }
+                }
+            }
+            let mut sizes = HashMap::<usize, usize>::new();
+            let roots = (0..actions.len())
+                .map(|i| root(&mut parents, i))
+                .collect::<Vec<_>>();
+            for root in &roots {
+                *sizes.entry(*root).or_default() += 1;
+            }
+            actions
+                .iter()
+                .zip(roots)
+                .filter(|(_, root)| sizes[root] > 1)
+                .map(|((k, _), root)| ((*k).clone(), root))
+                .collect()
+        })
+    }
+
+    /// Allows two actions to share inputs. Their bindings are no longer reported as conflicts and
+    /// neither suppresses the other at runtime.
+    pub fn allow_shared_inputs<K: Into<T>>(&mut self, action: K, other: K) -> &mut Self {
+        let (action, other) = (action.into(), other.into());
+        self.shared.insert((action.clone(), other.clone()));
+        self.shared.insert((other, action));
+        self
+    }
+
+    /// Returns whether two actions were allowed to share inputs with
+    /// [`InputMap::allow_shared_inputs`].
+    pub fn shares_inputs(&self, action: &T, other: &T) -> bool {
+        self.shared.contains(&(action.clone(), other.clone()))
+    }
+
+    /// Reports every pair of bindings of different actions that share inputs.
+    pub fn conflicts(&self) -> Vec<BindingConflict<T>> {
+        self.conflicts_in(self.actions.keys().cloned())
+    }
+
+    /// Reports every pair of bindings that share inputs among the given actions, eg. those
+    /// available in the same menu or game mode.
+    pub fn conflicts_in<I: IntoIterator<Item = T>>(&self, actions: I) -> Vec<BindingConflict<T>> {
+        let actions = actions
+            .into_iter()
+            .filter_map(|k| self.actions.get(&k).map(|v| (k, v)))
+            .collect::<Vec<_>>();
+        let mut conflicts = vec![];
+        for (i, (action, bindings)) in actions.iter().enumerate() {
+            for (other_action, other_bindings) in &actions[i + 1..] {
+                if action == other_action || self.shares_inputs(action, other_action) {
+                    continue;
+                }
+                for binding in &bindings.bindings {
+                    for other_binding in &other_bindings.bindings {
+                        conflicts.extend(BindingConflict::new(
+                            action.clone(),
+                            binding.clone(),
+                            other_action.clone(),
+                            other_binding.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Reports the existing bindings of other actions that would share inputs with the given
+    /// binding were it bound to the given action, eg. to warn that a key is already in use while
+    /// rebinding.
+    pub fn conflicts_with<K: Into<T>, B: Into<Binding>>(
+        &self,
+        action: K,
+        binding: B,
+    ) -> Vec<BindingConflict<T>> {
+        let (action, binding) = (action.into(), binding.into());
+        let mut conflicts = vec![];
+        for (other_action, other_bindings) in &self.actions {
+            if *other_action == action || self.shares_inputs(&action, other_action) {
+                continue;
+            }
+            for other_binding in &other_bindings.bindings {
+                conflicts.extend(BindingConflict::new(
+                    action.clone(),
+                    binding.clone(),
+                    other_action.clone(),
+                    other_binding.clone(),
+                ));
+            }
+        }
+        conflicts
+    }
+}
+
+/// Finds the group of the given action among the groups being merged
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+#[test]
+fn test_conflicts() {
+    use bevy::prelude::KeyCode;
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .bind("confirm", KeyCode::Space)
+        .bind("save", vec![KeyCode::LControl, KeyCode::S])
+        .bind("back", KeyCode::S)
+        .bind("select_all", vec![KeyCode::LControl, KeyCode::A]);
+    let conflicts = map.conflicts_with("jump", KeyCode::Space);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].other_action, "confirm");
+    assert_eq!(conflicts[0].kind, ConflictKind::Identical);
+    let conflicts = map.conflicts_in(vec!["save".to_string(), "back".to_string()]);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].action, "save");
+    assert_eq!(conflicts[0].kind, ConflictKind::Shadows);
+    assert_eq!(map.conflicts().len(), 3);
+    map.allow_shared_inputs("jump", "confirm");
+    assert_eq!(map.conflicts().len(), 2);
+    assert!(map
+        .conflicts()
+        .iter()
+        .any(|v| v.kind == ConflictKind::Partial));
+}
+
+#[test]
+fn test_conflict_strategies() {
+    use std::time::Duration;
+
+    use bevy::prelude::{Input, KeyCode};
+
+    let mut map = InputMap::<String>::default();
+    map.bind("save", vec![KeyCode::LControl, KeyCode::S])
+        .bind("back", KeyCode::S)
+        .set_priority("back", 1);
+    let mut input = Input::<KeyCode>::default();
+    input.press(KeyCode::LControl);
+    input.press(KeyCode::S);
+    let resolve = |map: &mut InputMap<String>, strategy| {
+        map.set_conflict_strategy(strategy);
+        map.update_keys(&input, &Default::default());
+        map.update_active(&input, &Default::default(), Duration::ZERO);
+        (map.active("save"), map.active("back"))
+    };
+    assert_eq!(
+        resolve(&mut map, ConflictStrategy::MostSpecific),
+        (true, false)
+    );
+    assert_eq!(resolve(&mut map, ConflictStrategy::AllFire), (true, true));
+    assert_eq!(resolve(&mut map, ConflictStrategy::Priority), (false, true));
+    let resolver = |action: &String, _: &Binding, _: &String, _: &Binding| action == "back";
+    assert_eq!(
+        resolve(&mut map, ConflictStrategy::Custom(Arc::new(resolver))),
+        (false, true)
+    );
+
+    // Conflicting actions are found again after rebinding
+    let mut map = InputMap::<String>::default();
+    map.bind("save", vec![KeyCode::LControl, KeyCode::S])
+        .bind("back", KeyCode::Escape);
+    assert_eq!(
+        resolve(&mut map, ConflictStrategy::MostSpecific),
+        (true, false)
+    );
+    map.replace_binding("back", 0, KeyCode::S);
+    assert_eq!(
+        resolve(&mut map, ConflictStrategy::MostSpecific),
+        (true, false)
+    );
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use bevy::prelude::Entity;
+
+/// An event describing a transition of an action, sent by [`crate::ActionPlugin`] when it is
+/// configured with [`crate::ActionPlugin::with_events`]. Events of an [`crate::InputMap`]
+/// component carry its entity, and those of the resource carry `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionEvent<T> {
+    /// The action has just become active.
+    Started {
+        action: T,
+        strength: f32,
+        entity: Option<Entity>,
+    },
+    /// The action was already active and still is.
+    Ongoing {
+        action: T,
+        strength: f32,
+        duration: Duration,
+        entity: Option<Entity>,
+    },
+    /// The action stopped being active because its inputs were released.
+    Completed {
+        action: T,
+        strength: f32,
+        duration: Duration,
+        entity: Option<Entity>,
+    },
+    /// The action stopped being active because it was cleared or superseded by a conflicting
+    /// binding while its inputs were still held.
+    Canceled {
+        action: T,
+        strength: f32,
+        duration: Duration,
+        entity: Option<Entity>,
+    },
+}
+
+impl<T> ActionEvent<T> {
+    /// Returns the action this event refers to.
+    pub fn action(&self) -> &T {
+        match self {
+            ActionEvent::Started { action, .. }
+            | ActionEvent::Ongoing { action, .. }
+            | ActionEvent::Completed { action, .. }
+            | ActionEvent::Canceled { action, .. } => action,
+        }
+    }
+
+    /// Returns the strength of the action when the event was generated.
+    pub fn strength(&self) -> f32 {
+        match self {
+            ActionEvent::Started { strength, .. }
+            | ActionEvent::Ongoing { strength, .. }
+            | ActionEvent::Completed { strength, .. }
+            | ActionEvent::Canceled { strength, .. } => *strength,
+        }
+    }
+
+    /// Returns how long the action has been active, which is zero for
+    /// [`ActionEvent::Started`].
+    pub fn duration(&self) -> Duration {
+        match self {
+            ActionEvent::Started { .. } => Duration::ZERO,
+            ActionEvent::Ongoing { duration, .. }
+            | ActionEvent::Completed { duration, .. }
+            | ActionEvent::Canceled { duration, .. } => *duration,
+        }
+    }
+
+    /// Returns the entity of the [`crate::InputMap`] component this event was sent for, or
+    /// `None` for the resource.
+    pub fn entity(&self) -> Option<Entity> {
+        match self {
+            ActionEvent::Started { entity, .. }
+            | ActionEvent::Ongoing { entity, .. }
+            | ActionEvent::Completed { entity, .. }
+            | ActionEvent::Canceled { entity, .. } => *entity,
+        }
+    }
+
+    pub(crate) fn with_entity(mut self, entity: Entity) -> Self {
+        match &mut self {
+            ActionEvent::Started { entity: e, .. }
+            | ActionEvent::Ongoing { entity: e, .. }
+            | ActionEvent::Completed { entity: e, .. }
+            | ActionEvent::Canceled { entity: e, .. } => *e = Some(entity),
+        }
+        self
+    }
+}
+
+#[test]
+fn test_events() {
+    use bevy::prelude::*;
+
+    use crate::InputMap;
+
+    fn update(
+        map: &mut InputMap<String>,
+        input: &mut Input<KeyCode>,
+        now: u64,
+    ) -> Vec<ActionEvent<String>> {
+        map.state.clear_just_active_inactive();
+        map.update_keys(input, &default());
+        map.update_active(input, &default(), Duration::from_millis(now));
+        input.clear();
+        map.events.clone()
+    }
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .bind("select", KeyCode::A)
+        .bind("select_all", vec![KeyCode::LControl, KeyCode::A]);
+    let mut input = Input::<KeyCode>::default();
+
+    input.press(KeyCode::Space);
+    input.press(KeyCode::A);
+    let events = update(&mut map, &mut input, 100);
+    assert_eq!(events.len(), 2);
+    assert!(events.contains(&ActionEvent::Started {
+        action: "jump".to_string(),
+        strength: 1.,
+        entity: None,
+    }));
+    let events = update(&mut map, &mut input, 250);
+    assert!(events.contains(&ActionEvent::Ongoing {
+        action: "jump".to_string(),
+        strength: 1.,
+        duration: Duration::from_millis(150),
+        entity: None,
+    }));
+
+    // Superseded by a more specific binding while still held
+    input.release(KeyCode::Space);
+    input.press(KeyCode::LControl);
+    let events = update(&mut map, &mut input, 400);
+    assert!(events.contains(&ActionEvent::Completed {
+        action: "jump".to_string(),
+        strength: 1.,
+        duration: Duration::from_millis(300),
+        entity: None,
+    }));
+    assert!(events.contains(&ActionEvent::Canceled {
+        action: "select".to_string(),
+        strength: 1.,
+        duration: Duration::from_millis(300),
+        entity: None,
+    }));
+    assert!(events.contains(&ActionEvent::Started {
+        action: "select_all".to_string(),
+        strength: 1.,
+        entity: None,
+    }));
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].duration(), Duration::from_millis(300));
+
+    // Held keys are reset by the plugin after a clear
+    map.clear();
+    input = default();
+    let events = update(&mut map, &mut input, 500);
+    assert_eq!(
+        events,
+        vec![ActionEvent::Canceled {
+            action: "select_all".to_string(),
+            strength: 1.,
+            duration: Duration::from_millis(100),
+            entity: None,
+        }]
+    );
+}
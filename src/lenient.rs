@@ -0,0 +1,559 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::{self, Debug},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use bevy::prelude::*;
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, StringDeserializer},
+        DeserializeSeed, EnumAccess, MapAccess, VariantAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::{
+    binding_string::ParseBindingError, versioned::NameSerializer, Action, Binding,
+    BindingOverrides, GamepadAxisDirection, InputMap, Modifier, ScanCode,
+};
+
+/// Why an entry was rejected while loading bindings leniently.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RejectionReason {
+    /// The action doesn't exist in the application's action type.
+    UnknownAction,
+    /// The binding names a key, button or axis direction that doesn't exist.
+    UnknownInput(String),
+    /// The binding's deadzone is outside of the range 0 to 1.
+    DeadzoneOutOfRange(f32),
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RejectionReason::UnknownAction => f.write_str("unknown action"),
+            RejectionReason::UnknownInput(name) => write!(f, "unknown input {}", name),
+            RejectionReason::DeadzoneOutOfRange(deadzone) => {
+                write!(f, "deadzone {} is outside of the range 0 to 1", deadzone)
+            }
+        }
+    }
+}
+
+impl From<ParseBindingError> for RejectionReason {
+    fn from(error: ParseBindingError) -> Self {
+        match error {
+            ParseBindingError::UnknownInput(name) => RejectionReason::UnknownInput(name),
+            ParseBindingError::InvalidDeadzone(deadzone) => match deadzone.parse() {
+                Ok(deadzone) => RejectionReason::DeadzoneOutOfRange(deadzone),
+                Err(_) => RejectionReason::UnknownInput(deadzone),
+            },
+        }
+    }
+}
+
+/// An action or binding that was skipped while loading bindings leniently.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection {
+    /// The name of the action, as written in the file.
+    pub action: String,
+    /// The position of the rejected binding among the action's bindings, or None if the whole
+    /// action was rejected.
+    pub binding: Option<usize>,
+    pub reason: RejectionReason,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(binding) = self.binding {
+            write!(
+                f,
+                "binding {} of action {}: {}",
+                binding, self.action, self.reason
+            )
+        } else {
+            write!(f, "action {}: {}", self.action, self.reason)
+        }
+    }
+}
+
+/// Deserializes bindings leniently, keeping every valid action and binding rather than failing
+/// on the first unknown action, unknown input name or out of range deadzone. The entries that were
+/// skipped are reported in `rejected`, so that the player can be warned and defaults used in their
+/// place. Input that isn't syntactically valid still fails to deserialize.
+///
+/// Supported for [`InputMap`] and [`BindingOverrides`]. [`VersionedBindings`] keep their bindings
+/// as strings until [`SaveFormat::load`], which rejects them the same way.
+///
+/// [`VersionedBindings`]: crate::VersionedBindings
+/// [`SaveFormat::load`]: crate::SaveFormat::load
+///
+/// ```
+/// # use bevy_input_actionmap::*;
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize)]
+/// enum Action {
+///     Jump,
+/// }
+///
+/// let saved = r#"{Jump: (bindings: ["Space", "Spacebar"]), Dance: (bindings: [])}"#;
+/// let loaded = ron::from_str::<Lenient<InputMap<Action>>>(saved).unwrap();
+/// assert_eq!(loaded.value.bindings(Action::Jump).len(), 1);
+/// assert_eq!(loaded.rejected.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct Lenient<V> {
+    pub value: V,
+    pub rejected: Vec<Rejection>,
+}
+
+impl<'de, T> Deserialize<'de> for Lenient<InputMap<T>>
+where
+    T: Deserialize<'de> + Serialize + Eq + Hash + Debug,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let lenient = Lenient::<BindingOverrides<T>>::deserialize(deserializer)?;
+        Ok(Lenient {
+            value: InputMap {
+                actions: lenient.value.actions,
+                ..default()
+            },
+            rejected: lenient.rejected,
+        })
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lenient<BindingOverrides<T>>
+where
+    T: Deserialize<'de> + Serialize + Eq + Hash + Debug,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(LenientMapVisitor(PhantomData))
+    }
+}
+
+struct LenientMapVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> Visitor<'de> for LenientMapVisitor<T>
+where
+    T: Deserialize<'de> + Serialize + Eq + Hash + Debug,
+{
+    type Value = Lenient<BindingOverrides<T>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Expected a valid Input mapping")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut value = BindingOverrides::default();
+        let mut rejected = vec![];
+        while let Some((key, action)) = access.next_entry::<Known<T>, LenientAction>()? {
+            match key.0 {
+                Ok(key) => {
+                    // Named as when saved, eg. `Jump` rather than `"Jump"` for string actions
+                    let name = key
+                        .serialize(NameSerializer)
+                        .unwrap_or_else(|_| format!("{:?}", key));
+                    let action = action.into_action(&name, &mut rejected);
+                    value.actions.insert(key, action);
+                }
+                Err(name) => rejected.push(Rejection {
+                    action: name,
+                    binding: None,
+                    reason: RejectionReason::UnknownAction,
+                }),
+            }
+        }
+        Ok(Lenient { value, rejected })
+    }
+}
+
+#[derive(Deserialize)]
+struct LenientAction {
+    #[serde(default)]
+    bindings: Vec<LenientBinding>,
+    #[serde(default)]
+    priority: i32,
+}
+
+impl LenientAction {
+    /// Keeps the valid bindings, recording why the others were rejected
+    fn into_action(self, name: &str, rejected: &mut Vec<Rejection>) -> Action {
+        let mut action = Action {
+            priority: self.priority,
+            ..default()
+        };
+        for (i, binding) in self.bindings.into_iter().enumerate() {
+            match binding.0 {
+                Ok(binding) => action.bindings.push(binding),
+                Err(reasons) => rejected.extend(reasons.into_iter().map(|reason| Rejection {
+                    action: name.to_string(),
+                    binding: Some(i),
+                    reason,
+                })),
+            }
+        }
+        action
+    }
+}
+
+/// A binding in its string form, or in the struct form saved by earlier versions
+struct LenientBinding(Result<Binding, Vec<RejectionReason>>);
+
+impl<'de> Deserialize<'de> for LenientBinding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = BindingText::deserialize(deserializer)?;
+        Ok(LenientBinding(
+            text.0.parse::<Binding>().map_err(|e| vec![e.into()]),
+        ))
+    }
+}
+
+/// A binding in the string syntax of [`binding_string`](crate::binding_string). Bindings in the
+/// struct form saved by earlier versions are converted to that syntax, keeping the names of
+/// unknown inputs so that they can still be fixed by a migration or reported.
+pub(crate) struct BindingText(pub(crate) String);
+
+impl<'de> Deserialize<'de> for BindingText {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BindingTextVisitor)
+        } else {
+            deserializer.deserialize_str(BindingTextVisitor)
+        }
+    }
+}
+
+struct BindingTextVisitor;
+
+impl<'de> Visitor<'de> for BindingTextVisitor {
+    type Value = BindingText;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a binding string such as \"LControl+S\"")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(BindingText(v.to_string()))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let fields = BindingFields::deserialize(MapAccessDeserializer::new(map))?;
+        Ok(BindingText(fields.into_string()))
+    }
+}
+
+#[derive(Deserialize)]
+struct BindingFields {
+    #[serde(default)]
+    keys: Vec<Known<KeyCode>>,
+    #[serde(default)]
+    modifiers: Vec<Known<Modifier>>,
+    #[serde(default)]
+    scan_codes: Vec<ScanCode>,
+    #[serde(default)]
+    mouse_buttons: Vec<Known<MouseButton>>,
+    #[serde(default)]
+    gamepad_buttons: Vec<Known<GamepadButtonType>>,
+    #[serde(default)]
+    gamepad_axis_directions: Vec<Known<GamepadAxisDirection>>,
+    #[serde(default)]
+    character: Option<char>,
+    #[serde(default)]
+    deadzone: f32,
+}
+
+impl BindingFields {
+    fn into_string(self) -> String {
+        fn known<V: Eq + Hash>(
+            values: Vec<Known<V>>,
+            prefix: &str,
+            unknown: &mut Vec<String>,
+        ) -> HashSet<V> {
+            let mut set = HashSet::new();
+            for value in values {
+                match value.0 {
+                    Ok(value) => {
+                        set.insert(value);
+                    }
+                    Err(name) => unknown.push(format!("{}{}", prefix, name)),
+                }
+            }
+            set
+        }
+
+        let mut unknown = vec![];
+        let binding = Binding {
+            keys: known(self.keys, "", &mut unknown),
+            modifiers: known(self.modifiers, "", &mut unknown),
+            scan_codes: self.scan_codes.into_iter().collect(),
+            mouse_buttons: known(self.mouse_buttons, "Mouse:", &mut unknown),
+            gamepad_buttons: known(self.gamepad_buttons, "Gamepad:", &mut unknown),
+            gamepad_axis_directions: known(self.gamepad_axis_directions, "", &mut unknown),
+            character: self.character,
+            ..default()
+        };
+        let mut inputs = binding.to_string();
+        for name in unknown {
+            if !inputs.is_empty() {
+                inputs.push('+');
+            }
+            inputs.push_str(&name);
+        }
+        if self.deadzone != 0. {
+            inputs.push_str(&format!(" > {}", self.deadzone));
+        }
+        inputs
+    }
+}
+
+/// A value of an enum type, or the name of the variant should it not exist in that type
+pub(crate) struct Known<V>(pub(crate) Result<V, String>);
+
+impl<'de, V> Deserialize<'de> for Known<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let unknown = RefCell::new(None);
+        let result = V::deserialize(CaptureUnknown {
+            inner: deserializer,
+            unknown: &unknown,
+        });
+        match (result, unknown.into_inner()) {
+            (_, Some(name)) => Ok(Known(Err(name))),
+            (Ok(value), None) => Ok(Known(Ok(value))),
+            (Err(e), None) => Err(e),
+        }
+    }
+}
+
+/// Wraps a deserializer to record the names of unknown enum variants. The unknown variant is
+/// consumed before failing, so that the caller can skip it and carry on deserializing.
+struct CaptureUnknown<'a, D> {
+    inner: D,
+    unknown: &'a RefCell<Option<String>>,
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a, D> Deserializer<'de> for CaptureUnknown<'a, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.deserialize_enum(
+            name,
+            variants,
+            CaptureUnknownVisitor {
+                inner: visitor,
+                variants,
+                unknown: self.unknown,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct CaptureUnknownVisitor<'a, V> {
+    inner: V,
+    variants: &'static [&'static str],
+    unknown: &'a RefCell<Option<String>>,
+}
+
+impl<'de, 'a, V> Visitor<'de> for CaptureUnknownVisitor<'a, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (name, variant) = data.variant_seed(VariantName)?;
+        if self.variants.contains(&name.as_str()) {
+            self.inner.visit_enum(NamedVariant { name, variant })
+        } else {
+            variant.unit_variant()?;
+            let error = de::Error::unknown_variant(&name, self.variants);
+            *self.unknown.borrow_mut() = Some(name);
+            Err(error)
+        }
+    }
+}
+
+/// Reads the name of an enum variant
+struct VariantName;
+
+impl<'de> DeserializeSeed<'de> for VariantName {
+    type Value = String;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for VariantName {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a variant name")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+}
+
+/// Replays an already read variant name to the visitor of the wrapped type
+struct NamedVariant<A> {
+    name: String,
+    variant: A,
+}
+
+impl<'de, A> EnumAccess<'de> for NamedVariant<A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = A;
+
+    fn variant_seed<S>(self, seed: S) -> Result<(S::Value, A), A::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(StringDeserializer::<A::Error>::new(self.name))?;
+        Ok((value, self.variant))
+    }
+}
+
+#[test]
+fn test_lenient() {
+    #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+    enum TestAction {
+        Jump,
+        Fire,
+    }
+
+    let saved = r#"{
+        Jump: (bindings: ["Space", (keys: [Spacebar], mouse_buttons: [Other(4)])]),
+        Fire: (bindings: ["Mouse:Left", "Gamepad:South > 2"]),
+        Dance: (bindings: ["D"]),
+    }"#;
+    let loaded = ron::from_str::<Lenient<InputMap<TestAction>>>(saved).expect("Failed");
+    assert_eq!(
+        loaded.value.bindings(TestAction::Jump),
+        vec![KeyCode::Space.into()]
+    );
+    assert_eq!(
+        loaded.value.bindings(TestAction::Fire),
+        vec![MouseButton::Left.into()]
+    );
+    assert_eq!(loaded.rejected.len(), 3);
+    assert!(loaded.rejected.contains(&Rejection {
+        action: "Jump".to_string(),
+        binding: Some(1),
+        reason: RejectionReason::UnknownInput("Spacebar".to_string()),
+    }));
+    assert!(loaded.rejected.contains(&Rejection {
+        action: "Fire".to_string(),
+        binding: Some(1),
+        reason: RejectionReason::DeadzoneOutOfRange(2.),
+    }));
+    assert!(loaded.rejected.contains(&Rejection {
+        action: "Dance".to_string(),
+        binding: None,
+        reason: RejectionReason::UnknownAction,
+    }));
+
+    let saved = r#"{"Jump": (bindings: ["Spacebar"])}"#;
+    let loaded = ron::from_str::<Lenient<InputMap<String>>>(saved).expect("Failed");
+    assert_eq!(loaded.rejected[0].action, "Jump");
+}
@@ -1,11 +1,16 @@
 use std::{
-    cmp::max,
     collections::{HashMap, HashSet},
     fmt::Debug,
     hash::Hash,
+    mem,
+    time::Duration,
 };
 
 use bevy::{
+    ecs::{
+        component::{Component, TableStorage},
+        event::{Events, ManualEventReader},
+    },
     input::{
         gamepad::{GamepadAxisType, GamepadEvent, GamepadEventType},
         InputSystem,
@@ -16,17 +21,64 @@ use bevy::{
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "asset")]
+mod asset;
+pub mod binding_string;
+mod chord;
+mod conflict;
+mod event;
+#[cfg(feature = "serialize")]
+mod lenient;
+mod modifier;
+mod overrides;
+mod rebind;
+mod recording;
+mod run_criteria;
+mod scan_code;
 #[cfg(feature = "serialize")]
 mod serialize;
+mod simulate;
+mod snapshot;
+mod state;
+#[cfg(feature = "serialize")]
+mod versioned;
+
+#[cfg(feature = "asset")]
+pub use asset::{InputMapAsset, InputMapAssetPlugin, InputMapLoader};
+pub use binding_string::ParseBindingError;
+pub use chord::ChordOrder;
+pub use conflict::{BindingConflict, ConflictKind, ConflictResolver, ConflictStrategy};
+pub use event::ActionEvent;
+#[cfg(feature = "serialize")]
+pub use lenient::{Lenient, Rejection, RejectionReason};
+pub use modifier::Modifier;
+pub use overrides::BindingOverrides;
+pub use rebind::{Rebind, RebindDevices, RebindEvent, RebindResult, Rebinder};
+pub use recording::{ActionPlayer, ActionRecorder, PlaybackMode, RecordedFrame, Recording};
+pub use run_criteria::*;
+pub use scan_code::{KeyboardLayout, ScanCode};
+pub use simulate::SimulateInput;
+pub use snapshot::{ActionSnapshot, SnapshotDiff};
+pub use state::ActionState;
+#[cfg(feature = "serialize")]
+pub use versioned::{LegacyBindings, SaveFormat, SavedAction, VersionedBindings};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
-/// A single binding consisting of sets of applicable key presses or gamepad activity meant to
-/// be used in tandem.
+/// A single binding consisting of sets of applicable key presses, mouse buttons or gamepad activity
+/// meant to be used in tandem.
 pub struct Binding {
     keys: HashSet<KeyCode>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    modifiers: HashSet<Modifier>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    scan_codes: HashSet<ScanCode>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    mouse_buttons: HashSet<MouseButton>,
     gamepad_buttons: HashSet<GamepadButtonType>,
     gamepad_axis_directions: HashSet<GamepadAxisDirection>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    character: Option<char>,
     deadzone: f32,
 }
 
@@ -51,6 +103,30 @@ impl From<Vec<KeyCode>> for Binding {
     }
 }
 
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        let mut buttons = HashSet::new();
+        buttons.insert(button);
+        Self {
+            mouse_buttons: buttons,
+            ..default()
+        }
+    }
+}
+
+impl From<Vec<MouseButton>> for Binding {
+    fn from(buttons: Vec<MouseButton>) -> Self {
+        let mut set = HashSet::new();
+        for button in buttons {
+            set.insert(button);
+        }
+        Self {
+            mouse_buttons: set,
+            ..default()
+        }
+    }
+}
+
 impl From<GamepadButtonType> for Binding {
     fn from(button: GamepadButtonType) -> Self {
         let mut buttons = HashSet::new();
@@ -92,6 +168,62 @@ pub enum GamepadAxisDirection {
     DPadYNegative,
 }
 
+impl GamepadAxisDirection {
+    /// Returns the direction an axis is pushed in given the sign of its value, along with the
+    /// opposite direction. Returns None for axes without directions.
+    pub(crate) fn from_axis(axis_type: GamepadAxisType, positive: bool) -> Option<(Self, Self)> {
+        use GamepadAxisDirection::*;
+        match axis_type {
+            GamepadAxisType::LeftStickX => Some(if positive {
+                (LeftStickXPositive, LeftStickXNegative)
+            } else {
+                (LeftStickXNegative, LeftStickXPositive)
+            }),
+            GamepadAxisType::LeftStickY => Some(if positive {
+                (LeftStickYPositive, LeftStickYNegative)
+            } else {
+                (LeftStickYNegative, LeftStickYPositive)
+            }),
+            GamepadAxisType::RightStickX => Some(if positive {
+                (RightStickXPositive, RightStickXNegative)
+            } else {
+                (RightStickXNegative, RightStickXPositive)
+            }),
+            GamepadAxisType::RightStickY => Some(if positive {
+                (RightStickYPositive, RightStickYNegative)
+            } else {
+                (RightStickYNegative, RightStickYPositive)
+            }),
+            GamepadAxisType::DPadX => Some(if positive {
+                (DPadXPositive, DPadXNegative)
+            } else {
+                (DPadXNegative, DPadXPositive)
+            }),
+            GamepadAxisType::DPadY => Some(if positive {
+                (DPadYPositive, DPadYNegative)
+            } else {
+                (DPadYNegative, DPadYPositive)
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<char> for Binding {
+    fn from(character: char) -> Self {
+        Self {
+            character: Some(character),
+            ..default()
+        }
+    }
+}
+
+impl From<Modifier> for Binding {
+    fn from(modifier: Modifier) -> Self {
+        Self::default().with_modifier(modifier)
+    }
+}
+
 impl From<GamepadAxisDirection> for Binding {
     fn from(gamepad_axis_direction: GamepadAxisDirection) -> Self {
         let mut gamepad_axis_directions = HashSet::new();
@@ -104,15 +236,132 @@ impl From<GamepadAxisDirection> for Binding {
 }
 
 impl Binding {
-    /// Searches a single binding for whether all of it's assigned keys are pressed
-    fn key_pressed(&self, input: &Res<Input<KeyCode>>) -> bool {
-        if self.keys.is_empty() {
+    /// Returns the keys that must be pressed together to trigger this binding.
+    pub fn keys(&self) -> &HashSet<KeyCode> {
+        &self.keys
+    }
+
+    /// Returns the modifiers that must be held along with the keys to trigger this binding.
+    pub fn modifiers(&self) -> &HashSet<Modifier> {
+        &self.modifiers
+    }
+
+    /// Requires a modifier to be held along with the keys, eg.
+    /// `Binding::from(KeyCode::S).with_modifier(Modifier::Primary)` for a save shortcut.
+    pub fn with_modifier(mut self, modifier: Modifier) -> Self {
+        self.modifiers.insert(modifier);
+        self
+    }
+
+    /// Returns the physical keys that must be pressed along with the keys to trigger this binding.
+    pub fn scan_codes(&self) -> &HashSet<ScanCode> {
+        &self.scan_codes
+    }
+
+    /// Returns the mouse buttons that must be pressed together to trigger this binding.
+    pub fn mouse_buttons(&self) -> &HashSet<MouseButton> {
+        &self.mouse_buttons
+    }
+
+    /// Returns the gamepad buttons that must be pressed together to trigger this binding.
+    pub fn gamepad_buttons(&self) -> &HashSet<GamepadButtonType> {
+        &self.gamepad_buttons
+    }
+
+    /// Returns the gamepad axis directions that must be pushed together to trigger this binding.
+    pub fn gamepad_axis_directions(&self) -> &HashSet<GamepadAxisDirection> {
+        &self.gamepad_axis_directions
+    }
+
+    /// Returns the character that triggers this binding when typed, whichever keys type it. Any
+    /// keys and modifiers of the binding must be held as it is typed, eg. for
+    /// `Binding::from('z').with_modifier(Modifier::Control)`.
+    pub fn character(&self) -> Option<char> {
+        self.character
+    }
+
+    /// Returns how far analog buttons and axes must be pushed to trigger this binding.
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    /// Returns whether this binding has any keys, modifiers or scan codes
+    fn has_keys(&self) -> bool {
+        !self.keys.is_empty() || !self.modifiers.is_empty() || !self.scan_codes.is_empty()
+    }
+
+    /// Returns whether this binding combines inputs of more than one device, eg. a key and a mouse
+    /// button. Such bindings only trigger once all of their inputs are held. Typed characters count
+    /// as a device of their own, so that eg. `Control` and a character only trigger once the
+    /// character is typed.
+    fn is_mixed(&self) -> bool {
+        let devices = [
+            self.character.is_some(),
+            self.has_keys(),
+            !self.mouse_buttons.is_empty(),
+            !self.gamepad_buttons.is_empty(),
+            !self.gamepad_axis_directions.is_empty(),
+        ];
+        devices.iter().filter(|v| **v).count() > 1
+    }
+
+    /// Returns whether this binding still triggers during text entry, which is the case for
+    /// bindings not using the keyboard, and those whose keys are all allowed.
+    fn allowed_in_text_entry(&self, allowed: &HashSet<KeyCode>) -> bool {
+        if !self.has_keys() && self.character.is_none() {
+            true
+        } else {
+            !self.keys.is_empty()
+                && self.keys.is_subset(allowed)
+                && self.scan_codes.is_empty()
+                && self.character.is_none()
+        }
+    }
+
+    /// Searches a single binding for whether all of it's assigned keys, modifiers and scan codes
+    /// are pressed
+    fn key_pressed(&self, input: &Input<KeyCode>, scan_codes: &Input<ScanCode>) -> bool {
+        if !self.has_keys() {
             false
         } else {
-            self.keys
+            self.keys.iter().all(|it| input.pressed(*it))
+                && self.modifiers.iter().all(|it| it.pressed(input))
+                && self.scan_codes.iter().all(|it| scan_codes.pressed(*it))
+        }
+    }
+
+    /// Returns whether any of the keys, modifiers or scan codes of this binding were just pressed
+    fn key_just_pressed(&self, input: &Input<KeyCode>, scan_codes: &Input<ScanCode>) -> bool {
+        self.keys.iter().any(|it| input.just_pressed(*it))
+            || self.modifiers.iter().any(|it| it.just_pressed(input))
+            || self
+                .scan_codes
                 .iter()
-                .map(|it| input.pressed(*it))
-                .fold(true, |it, acc| acc && it)
+                .any(|it| scan_codes.just_pressed(*it))
+    }
+
+    /// Returns whether this binding was just triggered by pressing one of its keys or mouse buttons,
+    /// rather than by releasing the inputs of a more specific binding. Gamepad inputs aren't
+    /// tracked across frames, so bindings using them always count as just triggered.
+    fn just_pressed(
+        &self,
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+        mouse_buttons: &Input<MouseButton>,
+    ) -> bool {
+        (!self.has_keys() && self.mouse_buttons.is_empty())
+            || !self.gamepad_buttons.is_empty()
+            || !self.gamepad_axis_directions.is_empty()
+            || self.key_just_pressed(input, scan_codes)
+            || mouse_buttons.any_just_pressed(self.mouse_buttons.iter().copied())
+    }
+
+    /// Searches a single binding for whether all of it's assigned mouse buttons are pressed
+    fn mouse_pressed(&self, input: &Input<MouseButton>) -> bool {
+        if self.mouse_buttons.is_empty() {
+            false
+        } else {
+            self.mouse_buttons.iter().all(|it| input.pressed(*it))
         }
     }
 
@@ -121,12 +370,9 @@ impl Binding {
         if self.gamepad_buttons.is_empty() {
             false
         } else {
-            self.gamepad_buttons
-                .iter()
-                .map(|it| {
-                    buttons.contains_key(it) && buttons.get(it).unwrap().abs() > self.deadzone
-                })
-                .fold(true, |it, acc| acc && it)
+            self.gamepad_buttons.iter().all(|it| {
+                buttons.contains_key(it) && buttons.get(it).unwrap().abs() > self.deadzone
+            })
         }
     }
 
@@ -137,14 +383,66 @@ impl Binding {
         } else {
             self.gamepad_axis_directions
                 .iter()
-                .map(|it| input.contains_key(it) && input.get(it).unwrap().abs() > self.deadzone)
-                .fold(true, |it, acc| acc && it)
+                .all(|it| input.contains_key(it) && input.get(it).unwrap().abs() > self.deadzone)
         }
     }
 
-    /// Describes how many keys or buttons must be pressed at once to trigger this binding
+    /// Describes how many inputs must be held at once to trigger this binding, across all devices
     fn weight(&self) -> usize {
-        max(self.keys.len(), self.gamepad_buttons.len())
+        self.keys.len()
+            + self.modifiers.len()
+            + self.scan_codes.len()
+            + self.mouse_buttons.len()
+            + self.gamepad_buttons.len()
+            + self.gamepad_axis_directions.len()
+            + self.character.iter().count()
+    }
+
+    /// Describes how many inputs this binding has in common with another, across all devices
+    fn overlap(&self, other: &Binding) -> usize {
+        self.key_overlap(other)
+            + self
+                .mouse_buttons
+                .intersection(&other.mouse_buttons)
+                .count()
+            + self
+                .gamepad_buttons
+                .intersection(&other.gamepad_buttons)
+                .count()
+            + self
+                .gamepad_axis_directions
+                .intersection(&other.gamepad_axis_directions)
+                .count()
+            + usize::from(self.character.is_some() && self.character == other.character)
+    }
+
+    /// Counts the keys and modifiers of this binding that are held by the same keys as distinct
+    /// keys or modifiers of another, so that `Control` matches either `LControl` or `RControl`
+    fn key_overlap(&self, other: &Binding) -> usize {
+        let mut keys = other.keys.iter().copied().collect::<Vec<_>>();
+        let mut modifiers = other.modifiers.iter().copied().collect::<Vec<_>>();
+        let mut overlap = self.scan_codes.intersection(&other.scan_codes).count();
+        for key in &self.keys {
+            if let Some(i) = keys.iter().position(|v| v == key) {
+                keys.remove(i);
+            } else if let Some(i) = modifiers.iter().position(|v| v.matches(*key)) {
+                modifiers.remove(i);
+            } else {
+                continue;
+            }
+            overlap += 1;
+        }
+        for modifier in &self.modifiers {
+            if let Some(i) = modifiers.iter().position(|v| v.aliases(modifier)) {
+                modifiers.remove(i);
+            } else if let Some(i) = keys.iter().position(|v| modifier.matches(*v)) {
+                keys.remove(i);
+            } else {
+                continue;
+            }
+            overlap += 1;
+        }
+        overlap
     }
 }
 
@@ -152,21 +450,69 @@ impl Binding {
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 /// An Action consists of many bindings of which any count as triggering it
 pub struct Action {
+    #[cfg_attr(
+        feature = "serialize",
+        serde(deserialize_with = "binding_string::vec::deserialize")
+    )]
     pub(crate) bindings: Vec<Binding>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "is_default_priority")
+    )]
+    pub(crate) priority: i32,
+}
+
+#[cfg(feature = "serialize")]
+fn is_default_priority(priority: &i32) -> bool {
+    *priority == 0
 }
 
 impl Action {
+    /// Returns the bindings of which any count as triggering this action.
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Returns the priority of this action for [`ConflictStrategy::Priority`].
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
     /// Searches all keypress Bindings for those being actively triggered and returns Some(Binding)
     /// of the Binding in question. Should multiple bindings be triggered at once, the one with the
     /// greatest [`Binding::weight`] is returned. Should no bindings be triggered, None is returned.
-    fn key_pressed(&self, input: &Res<Input<KeyCode>>) -> Option<Binding> {
+    /// Only the bindings for which `allowed` returns true are searched, eg. during text entry.
+    fn key_pressed(
+        &self,
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+        allowed: impl Fn(&Binding) -> bool,
+    ) -> Option<Binding> {
+        let mut bindings = self
+            .bindings
+            .iter()
+            .filter(|it| allowed(it))
+            .filter(|it| !it.is_mixed())
+            .filter(|it| it.key_pressed(input, scan_codes))
+            .cloned()
+            .collect::<Vec<Binding>>();
+        bindings.sort_by(|v1, v2| v1.weight().partial_cmp(&v2.weight()).unwrap());
+        bindings.last().cloned()
+    }
+
+    /// Searches all mouse button Bindings for those being actively triggered and returns
+    /// Some(Binding) of the Binding in question. Should multiple bindings be triggered at once, the
+    /// one with the greatest [`Binding::weight`] is returned. Should no bindings be triggered, None
+    /// is returned.
+    fn mouse_pressed(&self, input: &Input<MouseButton>) -> Option<Binding> {
         let mut bindings = self
             .bindings
             .iter()
-            .filter(|it| it.key_pressed(input))
+            .filter(|it| !it.is_mixed())
+            .filter(|it| it.mouse_pressed(input))
             .cloned()
             .collect::<Vec<Binding>>();
-        bindings.sort_by(|v1, v2| (v1.weight().partial_cmp(&v2.weight()).unwrap()));
+        bindings.sort_by(|v1, v2| v1.weight().partial_cmp(&v2.weight()).unwrap());
         bindings.last().cloned()
     }
 
@@ -178,10 +524,11 @@ impl Action {
         let mut bindings = self
             .bindings
             .iter()
+            .filter(|it| !it.is_mixed())
             .filter(|it| it.button_pressed(buttons))
             .cloned()
             .collect::<Vec<Binding>>();
-        bindings.sort_by(|v1, v2| (v1.weight().partial_cmp(&v2.weight()).unwrap()));
+        bindings.sort_by(|v1, v2| v1.weight().partial_cmp(&v2.weight()).unwrap());
         let binding = bindings.last().cloned();
         if let Some(binding) = binding {
             let mut strength = 0.;
@@ -197,6 +544,56 @@ impl Action {
         }
     }
 
+    /// Searches all Bindings combining inputs of several devices for those whose inputs are all
+    /// held, and returns Some((Binding, f32)) of the Binding and its strength, that of its axes if
+    /// it has any, otherwise that of its gamepad buttons, otherwise 1. Should multiple bindings be
+    /// triggered at once, the one with the greatest [`Binding::weight`] is returned. Should no
+    /// bindings be triggered, None is returned. Only the bindings for which `allowed` returns true
+    /// are searched.
+    fn mixed_pressed(
+        &self,
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+        allowed: impl Fn(&Binding) -> bool,
+        mouse_buttons: &Input<MouseButton>,
+        buttons: &HashMap<GamepadButtonType, f32>,
+        directions: &HashMap<GamepadAxisDirection, f32>,
+    ) -> Option<(Binding, f32)> {
+        let mut bindings = self
+            .bindings
+            .iter()
+            .filter(|it| it.is_mixed() && it.character.is_none())
+            .filter(|it| allowed(it))
+            .filter(|it| !it.has_keys() || it.key_pressed(input, scan_codes))
+            .filter(|it| it.mouse_buttons.is_empty() || it.mouse_pressed(mouse_buttons))
+            .filter(|it| it.gamepad_buttons.is_empty() || it.button_pressed(buttons))
+            .filter(|it| {
+                it.gamepad_axis_directions.is_empty() || it.gamepad_axis_changed(directions)
+            })
+            .cloned()
+            .collect::<Vec<Binding>>();
+        bindings.sort_by(|v1, v2| v1.weight().partial_cmp(&v2.weight()).unwrap());
+        let binding = bindings.last().cloned()?;
+        let strength = if !binding.gamepad_axis_directions.is_empty() {
+            binding
+                .gamepad_axis_directions
+                .iter()
+                .map(|v| directions[v])
+                .sum::<f32>()
+                / binding.gamepad_axis_directions.len() as f32
+        } else if !binding.gamepad_buttons.is_empty() {
+            binding
+                .gamepad_buttons
+                .iter()
+                .map(|v| buttons[v])
+                .sum::<f32>()
+                / binding.gamepad_buttons.len() as f32
+        } else {
+            1.
+        };
+        Some((binding, strength))
+    }
+
     /// Searches all gamepad axis Bindings for those being actively triggered and returns
     /// Some((Binding, f32)) of the Binding and the strength of the axis pull in question. Should
     /// multiple bindings be triggered at once, the one with the greatest [`Binding::weight`] is
@@ -208,10 +605,11 @@ impl Action {
         let mut bindings = self
             .bindings
             .iter()
+            .filter(|it| !it.is_mixed())
             .filter(|it| it.gamepad_axis_changed(directions))
             .cloned()
             .collect::<Vec<Binding>>();
-        bindings.sort_by(|v1, v2| (v1.weight().partial_cmp(&v2.weight()).unwrap()));
+        bindings.sort_by(|v1, v2| v1.weight().partial_cmp(&v2.weight()).unwrap());
         let binding = bindings.last().cloned();
         if let Some(binding) = binding {
             let mut strength = 0.;
@@ -228,18 +626,41 @@ impl Action {
     }
 }
 
-/// A Bevy resource tracking bound `Action`s (including [`KeyCode`]s, [`GamepadButtonType`]s, and
-/// [`GamepadAxisDirection`]s) generic over the application's action event type.
+/// A Bevy resource tracking bound `Action`s (including [`KeyCode`]s, [`MouseButton`]s,
+/// [`GamepadButtonType`]s, and [`GamepadAxisDirection`]s) generic over the application's action event type.
+///
+/// An `InputMap` may also be added to entities as a component, in which case each entity tracks
+/// its own bindings and triggered actions. Add an [`ActionState`] component to the same entity to
+/// query those actions independently of the bindings.
 #[derive(Debug)]
 pub struct InputMap<T> {
     pub(crate) actions: HashMap<T, Action>,
+    defaults: HashMap<T, Action>,
     pressed_buttons: HashMap<GamepadButtonType, f32>,
+    mouse_buttons: Input<MouseButton>,
     gamepad_axis: HashMap<GamepadAxisDirection, f32>,
     raw_active: Vec<(T, Binding, f32)>,
-    active: HashMap<T, f32>,
-    just_active: HashMap<T, f32>,
-    just_inactive: HashSet<T>,
+    state: ActionState<T>,
+    shared: HashSet<(T, T)>,
+    started: HashMap<T, Duration>,
+    canceled: HashMap<T, f32>,
+    events: Vec<ActionEvent<T>>,
     gamepads: HashSet<Gamepad>,
+    gamepad: Option<Gamepad>,
+    typed: HashSet<T>,
+    text_entry: bool,
+    text_entry_keys: HashSet<KeyCode>,
+    active_bindings: HashMap<T, Vec<Binding>>,
+    conflict_strategy: ConflictStrategy<T>,
+    conflict_groups: Option<HashMap<T, usize>>,
+    chord_order: ChordOrder,
+    key_presses: HashMap<KeyCode, Duration>,
+    scan_code_presses: HashMap<ScanCode, Duration>,
+    last_update: Duration,
+    simulated: HashMap<T, f32>,
+    live_input: bool,
+    replayed: Option<RecordedFrame<T>>,
+    restored: Option<ActionState<T>>,
     wants_clear: bool,
 }
 
@@ -247,18 +668,46 @@ impl<T> Default for InputMap<T> {
     fn default() -> Self {
         Self {
             actions: HashMap::new(),
+            defaults: HashMap::new(),
             pressed_buttons: HashMap::new(),
+            mouse_buttons: default(),
             gamepad_axis: HashMap::new(),
             raw_active: Vec::new(),
-            active: HashMap::new(),
-            just_active: HashMap::new(),
-            just_inactive: HashSet::new(),
+            state: default(),
+            shared: HashSet::new(),
+            started: HashMap::new(),
+            canceled: HashMap::new(),
+            events: Vec::new(),
             gamepads: HashSet::new(),
+            gamepad: None,
+            typed: HashSet::new(),
+            text_entry: false,
+            text_entry_keys: [KeyCode::Escape, KeyCode::Return, KeyCode::NumpadEnter]
+                .into_iter()
+                .collect(),
+            active_bindings: HashMap::new(),
+            conflict_strategy: default(),
+            conflict_groups: None,
+            chord_order: default(),
+            key_presses: HashMap::new(),
+            scan_code_presses: HashMap::new(),
+            last_update: Duration::ZERO,
+            simulated: HashMap::new(),
+            live_input: true,
+            replayed: None,
+            restored: None,
             wants_clear: false,
         }
     }
 }
 
+impl<T> Component for InputMap<T>
+where
+    T: 'static + Send + Sync,
+{
+    type Storage = TableStorage;
+}
+
 impl<T> InputMap<T>
 where
     T: Hash + Eq + Clone + Send + Sync,
@@ -266,6 +715,7 @@ where
     /// Adds an instance of the application's action type to the list of actions, but with no bound
     /// inputs.
     pub fn add_action(&mut self, key: T) -> &mut Self {
+        self.conflict_groups = None;
         self.actions.insert(key, default());
         self
     }
@@ -280,6 +730,7 @@ where
         if let Some(actions) = self.actions.get_mut(&key) {
             actions.bindings.push(binding.into());
         }
+        self.conflict_groups = None;
         self
     }
 
@@ -300,31 +751,166 @@ where
             binding.deadzone = deadzone;
             actions.bindings.push(binding);
         }
+        self.conflict_groups = None;
         self
     }
 
+    /// Returns the bindings of the given action, which is empty if the action was never added.
+    pub fn bindings<K: Into<T>>(&self, action: K) -> &[Binding] {
+        self.actions
+            .get(&action.into())
+            .map(|v| v.bindings())
+            .unwrap_or_default()
+    }
+
+    /// Iterates over every added action along with its bindings.
+    pub fn actions(&self) -> impl Iterator<Item = (&T, &[Binding])> {
+        self.actions.iter().map(|(k, v)| (k, v.bindings()))
+    }
+
+    /// Removes every occurrence of the given binding from the given action, returning whether any
+    /// were found.
+    pub fn unbind<K: Into<T>, B: Into<Binding>>(&mut self, action: K, binding: B) -> bool {
+        let binding = binding.into();
+        self.conflict_groups = None;
+        if let Some(action) = self.actions.get_mut(&action.into()) {
+            let len = action.bindings.len();
+            action.bindings.retain(|v| *v != binding);
+            action.bindings.len() != len
+        } else {
+            false
+        }
+    }
+
+    /// Removes all bindings from the given action without removing the action itself.
+    pub fn unbind_all<K: Into<T>>(&mut self, action: K) -> &mut Self {
+        self.conflict_groups = None;
+        if let Some(action) = self.actions.get_mut(&action.into()) {
+            action.bindings.clear();
+        }
+        self
+    }
+
+    /// Replaces the binding at the given index of the given action's bindings, returning the
+    /// binding that was replaced. Returns None and leaves the bindings unchanged should the action
+    /// not have a binding at that index.
+    pub fn replace_binding<K: Into<T>, B: Into<Binding>>(
+        &mut self,
+        action: K,
+        index: usize,
+        binding: B,
+    ) -> Option<Binding> {
+        self.conflict_groups = None;
+        let current = self
+            .actions
+            .get_mut(&action.into())
+            .and_then(|v| v.bindings.get_mut(index))?;
+        Some(mem::replace(current, binding.into()))
+    }
+
+    /// Removes the given action along with all of its bindings, returning it if it was added.
+    pub fn remove_action<K: Into<T>>(&mut self, action: K) -> Option<Action> {
+        self.conflict_groups = None;
+        self.actions.remove(&action.into())
+    }
+
     /// Returns whether a given action is currently triggered.
     pub fn active<K: Into<T>>(&self, key: K) -> bool {
-        self.active.contains_key(&key.into())
+        self.state.active(key)
     }
 
     /// Returns whether a given action has just been triggered.
     pub fn just_active<K: Into<T>>(&self, key: K) -> bool {
-        self.just_active.contains_key(&key.into())
+        self.state.just_active(key)
     }
 
     /// Returns whether a given action has just stopped being triggered.
     pub fn just_inactive<K: Into<T>>(&self, key: K) -> bool {
-        self.just_inactive.contains(&key.into())
+        self.state.just_inactive(key)
     }
 
     /// Returns the strength of an active triggered action for use with analog input.
     pub fn strength<K: Into<T>>(&self, key: K) -> f32 {
-        if let Some(strength) = self.active.get(&key.into()) {
-            *strength
-        } else {
-            0.
+        self.state.strength(key)
+    }
+
+    /// Returns whether a given action has been triggered since the previous fixed tick. See
+    /// [`tick_fixed_actions`].
+    pub fn fixed_just_active<K: Into<T>>(&self, key: K) -> bool {
+        self.state.fixed_just_active(key)
+    }
+
+    /// Returns whether a given action has stopped being triggered since the previous fixed tick.
+    /// See [`tick_fixed_actions`].
+    pub fn fixed_just_inactive<K: Into<T>>(&self, key: K) -> bool {
+        self.state.fixed_just_inactive(key)
+    }
+
+    /// Returns the triggered actions of this map.
+    pub fn state(&self) -> &ActionState<T> {
+        &self.state
+    }
+
+    /// Starts a new fixed tick. See [`ActionState::tick_fixed`].
+    pub fn tick_fixed(&mut self) {
+        self.state.tick_fixed();
+    }
+
+    /// Restricts gamepad bindings to the given gamepad, eg. to give each player entity its own
+    /// controller. With None, the default, input from every gamepad is mapped.
+    pub fn set_gamepad(&mut self, gamepad: Option<Gamepad>) -> &mut Self {
+        if gamepad != self.gamepad {
+            self.pressed_buttons.clear();
+            self.gamepad_axis.clear();
         }
+        self.gamepad = gamepad;
+        self
+    }
+
+    /// Returns the gamepad whose input is mapped, or None if input from every gamepad is.
+    pub fn gamepad(&self) -> Option<Gamepad> {
+        self.gamepad
+    }
+
+    /// Sets whether a text field has focus, eg. while a chat box or console is open. While it
+    /// does, bindings using the keyboard don't trigger unless all of their keys are allowed with
+    /// [`InputMap::allow_during_text_entry`], while mouse and gamepad bindings keep working.
+    /// Actions held through the keyboard when text entry starts become inactive.
+    pub fn set_text_entry(&mut self, text_entry: bool) -> &mut Self {
+        if text_entry && !self.text_entry {
+            let suspended = self
+                .active_bindings
+                .iter()
+                .filter(|(_, v)| {
+                    !v.iter()
+                        .any(|v| v.allowed_in_text_entry(&self.text_entry_keys))
+                })
+                .map(|(k, _)| k.clone())
+                .collect::<Vec<_>>();
+            for k in suspended {
+                self.cancel(&k);
+            }
+        }
+        self.text_entry = text_entry;
+        self
+    }
+
+    /// Allows bindings using the given key to trigger during text entry. Escape, Return and
+    /// NumpadEnter are allowed by default, eg. to close or submit the text field.
+    pub fn allow_during_text_entry(&mut self, key: KeyCode) -> &mut Self {
+        self.text_entry_keys.insert(key);
+        self
+    }
+
+    /// Stops bindings using the given key from triggering during text entry.
+    pub fn disallow_during_text_entry(&mut self, key: KeyCode) -> &mut Self {
+        self.text_entry_keys.remove(&key);
+        self
+    }
+
+    /// Returns whether a text field has focus, as set with [`InputMap::set_text_entry`].
+    pub fn text_entry(&self) -> bool {
+        self.text_entry
     }
 
     /// Clears all triggered actions without changing configured bindings.
@@ -333,212 +919,524 @@ where
         self.pressed_buttons.clear();
         self.gamepad_axis.clear();
         self.raw_active.clear();
-        self.active.clear();
-        self.just_active.clear();
-        self.just_inactive.clear();
+        self.simulated.clear();
+        self.key_presses.clear();
+        self.scan_code_presses.clear();
+        self.canceled.extend(self.state.active.drain());
+        self.state.clear();
     }
 
-    /// System that clears specifically the maps of just active or just inactive actions
-    fn clear_just_active_inactive(mut input_map: ResMut<InputMap<T>>)
-    where
-        T: 'static + Debug,
-    {
-        input_map.just_active.clear();
-        input_map.just_inactive.clear();
+    /// Replaces the bindings with those of another map, eg. one deserialized from a config file.
+    /// Connected gamepads, held inputs and the state of actions whose bindings didn't change are
+    /// kept, while active actions whose bindings changed are canceled.
+    pub fn apply_bindings(&mut self, other: InputMap<T>) -> &mut Self {
+        self.set_actions(other.actions);
+        self
     }
 
-    /// System that listens to pressed [`KeyCodes`] to map to the configured actions
-    fn key_input(input: Res<Input<KeyCode>>, mut input_map: ResMut<InputMap<T>>)
-    where
-        T: 'static + Debug,
-    {
-        let mut raw_active = input_map
+    /// Replaces the bindings of every action, canceling active actions whose bindings changed.
+    pub(crate) fn set_actions(&mut self, actions: HashMap<T, Action>) {
+        let changed = self
+            .state
+            .active
+            .keys()
+            .filter(|k| self.actions.get(k) != actions.get(k))
+            .cloned()
+            .collect::<Vec<_>>();
+        for k in changed {
+            self.cancel(&k);
+        }
+        self.actions = actions;
+        self.conflict_groups = None;
+    }
+
+    /// Ends an active action as though its inputs had been interrupted.
+    pub(crate) fn cancel(&mut self, action: &T) {
+        if let Some(strength) = self.state.cancel(action) {
+            self.canceled.insert(action.clone(), strength);
+        }
+    }
+
+    /// Maps pressed [`KeyCode`]s and [`ScanCode`]s to the configured actions
+    fn update_keys(&mut self, input: &Input<KeyCode>, scan_codes: &Input<ScanCode>) {
+        self.track_key_presses(input, scan_codes);
+        let mut raw_active = self
             .actions
             .iter()
-            .map(|a| (a.0, a.1.key_pressed(&input)))
+            .map(|a| {
+                (
+                    a.0,
+                    a.1.key_pressed(input, scan_codes, |v| self.keys_allowed(v)),
+                )
+            })
             .filter(|v| v.1.is_some())
             .map(|v| (v.0.clone(), v.1.unwrap(), 1.))
             .collect::<Vec<(T, Binding, f32)>>();
-        input_map.raw_active.append(&mut raw_active);
+        self.raw_active.append(&mut raw_active);
     }
 
-    /// System that listens to [`GamepadEvent`]s to write into the raw inputs
-    fn gamepad_state(mut gamepad_events: EventReader<GamepadEvent>, mut input: ResMut<InputMap<T>>)
-    where
-        T: 'static + Debug,
-    {
-        for event in gamepad_events.iter() {
-            match &event {
-                GamepadEvent(gamepad, GamepadEventType::Connected) => {
-                    input.gamepads.insert(*gamepad);
-                }
-                GamepadEvent(gamepad, GamepadEventType::Disconnected) => {
-                    input.gamepads.remove(gamepad);
+    /// Returns whether a binding may trigger given text entry and the [`ChordOrder`]
+    fn keys_allowed(&self, binding: &Binding) -> bool {
+        (!self.text_entry || binding.allowed_in_text_entry(&self.text_entry_keys))
+            && self.in_chord_order(binding)
+    }
+
+    /// Maps pressed [`MouseButton`]s to the configured actions
+    fn update_mouse_buttons(&mut self, input: &Input<MouseButton>) {
+        self.mouse_buttons = input.clone();
+        let mut raw_active = self
+            .actions
+            .iter()
+            .map(|a| (a.0, a.1.mouse_pressed(input)))
+            .filter(|v| v.1.is_some())
+            .map(|v| (v.0.clone(), v.1.unwrap(), 1.))
+            .collect::<Vec<(T, Binding, f32)>>();
+        self.raw_active.append(&mut raw_active);
+    }
+
+    /// Maps characters typed this frame to the configured actions, for bindings whose keys and
+    /// modifiers, if any, are held
+    fn update_characters(
+        &mut self,
+        characters: &[char],
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+    ) {
+        if self.text_entry {
+            return;
+        }
+        for (k, v) in &self.actions {
+            if let Some(binding) = v.bindings.iter().find(|it| {
+                it.character.is_some_and(|c| characters.contains(&c))
+                    && (!it.has_keys() || it.key_pressed(input, scan_codes))
+            }) {
+                self.raw_active.push((k.clone(), binding.clone(), 1.));
+                self.typed.insert(k.clone());
+            }
+        }
+    }
+
+    /// Writes a [`GamepadEvent`] into the raw inputs, unless it comes from a gamepad other than
+    /// that set with [`InputMap::set_gamepad`]
+    fn update_gamepad(&mut self, event: &GamepadEvent) {
+        if self.gamepad.is_some_and(|v| v != event.0) {
+            return;
+        }
+        match &event {
+            GamepadEvent(gamepad, GamepadEventType::Connected) => {
+                self.gamepads.insert(*gamepad);
+            }
+            GamepadEvent(gamepad, GamepadEventType::Disconnected) => {
+                self.gamepads.remove(gamepad);
+            }
+            GamepadEvent(_, GamepadEventType::ButtonChanged(button, strength)) => {
+                if strength > &0. {
+                    self.pressed_buttons.insert(*button, *strength);
+                } else {
+                    self.pressed_buttons.remove(button);
                 }
-                GamepadEvent(_, GamepadEventType::ButtonChanged(button, strength)) => {
-                    if strength > &0. {
-                        input.pressed_buttons.insert(*button, *strength);
+            }
+            GamepadEvent(_, GamepadEventType::AxisChanged(axis_type, strength)) => {
+                let direction = GamepadAxisDirection::from_axis(*axis_type, *strength >= 0.);
+                if let Some((direction, opposite)) = direction {
+                    if *strength != 0. {
+                        self.gamepad_axis.insert(direction, *strength);
                     } else {
-                        input.pressed_buttons.remove(button);
-                    }
-                }
-                GamepadEvent(_, GamepadEventType::AxisChanged(axis_type, strength)) => {
-                    use GamepadAxisDirection::*;
-                    let positive = *strength >= 0.;
-                    let direction = match axis_type {
-                        GamepadAxisType::LeftStickX => Some(if positive {
-                            (LeftStickXPositive, LeftStickXNegative)
-                        } else {
-                            (LeftStickXNegative, LeftStickXPositive)
-                        }),
-                        GamepadAxisType::LeftStickY => Some(if positive {
-                            (LeftStickYPositive, LeftStickYNegative)
-                        } else {
-                            (LeftStickYNegative, LeftStickYPositive)
-                        }),
-                        GamepadAxisType::RightStickX => Some(if positive {
-                            (RightStickXPositive, RightStickXNegative)
-                        } else {
-                            (RightStickXNegative, RightStickXPositive)
-                        }),
-                        GamepadAxisType::RightStickY => Some(if positive {
-                            (RightStickYPositive, RightStickYNegative)
-                        } else {
-                            (RightStickYNegative, RightStickYPositive)
-                        }),
-                        GamepadAxisType::DPadX => Some(if positive {
-                            (DPadXPositive, DPadXNegative)
-                        } else {
-                            (DPadXNegative, DPadXPositive)
-                        }),
-                        GamepadAxisType::DPadY => Some(if positive {
-                            (DPadYPositive, DPadYNegative)
-                        } else {
-                            (DPadYNegative, DPadYPositive)
-                        }),
-                        _ => None,
-                    };
-                    if let Some((direction, opposite)) = direction {
-                        if *strength != 0. {
-                            input.gamepad_axis.insert(direction, *strength);
-                        } else {
-                            input.gamepad_axis.remove(&direction);
-                        }
-                        input.gamepad_axis.remove(&opposite);
+                        self.gamepad_axis.remove(&direction);
                     }
+                    self.gamepad_axis.remove(&opposite);
                 }
             }
         }
     }
 
-    /// System that updates the gamepad button inputs in the [`InputMap`]
-    fn gamepad_button_input(mut input_map: ResMut<InputMap<T>>)
-    where
-        T: 'static + Debug,
-    {
-        let mut raw_active = input_map
+    /// Maps the pressed gamepad buttons to the configured actions
+    fn update_gamepad_buttons(&mut self) {
+        let mut raw_active = self
             .actions
             .iter()
-            .map(|a| (a.0, a.1.button_pressed(&input_map.pressed_buttons)))
+            .map(|a| (a.0, a.1.button_pressed(&self.pressed_buttons)))
             .filter(|v| v.1.is_some())
             .map(|v| {
                 let press = v.1.unwrap();
                 (v.0.clone(), press.0, press.1)
             })
             .collect::<Vec<(T, Binding, f32)>>();
-        input_map.raw_active.append(&mut raw_active);
+        self.raw_active.append(&mut raw_active);
     }
 
-    /// System that updates the gamepad axis inputs in the [`InputMap`]
-    fn gamepad_axis_input(mut input_map: ResMut<InputMap<T>>)
-    where
-        T: 'static + Debug,
-    {
-        let mut raw_active = input_map
+    /// Maps the gamepad axis directions to the configured actions
+    fn update_gamepad_axes(&mut self) {
+        let mut raw_active = self
             .actions
             .iter()
-            .map(|a| (a.0, a.1.gamepad_axis_changed(&input_map.gamepad_axis)))
+            .map(|a| (a.0, a.1.gamepad_axis_changed(&self.gamepad_axis)))
             .filter(|v| v.1.is_some())
             .map(|v| {
                 let rv = v.1.unwrap();
                 (v.0.clone(), rv.0, rv.1)
             })
             .collect::<Vec<(T, Binding, f32)>>();
-        input_map.raw_active.append(&mut raw_active);
+        self.raw_active.append(&mut raw_active);
     }
 
-    /// System that prunes conflicting actions by prioritizing that with the higher weight.
-    fn resolve_conflicts(mut input_map: ResMut<InputMap<T>>, input: Res<Input<KeyCode>>)
-    where
-        T: 'static + Debug,
-    {
-        let mut active_resolve_conflicts = input_map.raw_active.clone();
-        for (outer_action, outer_binding, outer_strength) in &input_map.raw_active {
-            for (inner_action, inner_binding, inner_strength) in &input_map.raw_active {
-                if outer_action == inner_action {
-                    continue;
-                }
-                let weight = if !outer_binding.keys.is_empty() && !inner_binding.keys.is_empty() {
-                    let intersection = outer_binding.keys.intersection(&inner_binding.keys);
-                    intersection.count()
-                } else if !outer_binding.gamepad_buttons.is_empty()
-                    && !inner_binding.gamepad_buttons.is_empty()
-                {
-                    let intersection = outer_binding
-                        .gamepad_buttons
-                        .intersection(&inner_binding.gamepad_buttons);
-                    intersection.count()
-                } else {
-                    0
-                };
-                if weight == outer_binding.weight() {
-                    continue;
-                }
-                if weight != 0 {
-                    let to_remove = if weight < outer_binding.weight() {
-                        (inner_action.clone(), inner_binding.clone(), *inner_strength)
-                    } else {
-                        (outer_action.clone(), outer_binding.clone(), *outer_strength)
-                    };
-                    active_resolve_conflicts.retain(|v| *v != to_remove);
+    /// Maps bindings combining inputs of several devices to the configured actions, once the inputs
+    /// of every device are known
+    fn update_mixed(&mut self, input: &Input<KeyCode>, scan_codes: &Input<ScanCode>) {
+        let mut raw_active = self
+            .actions
+            .iter()
+            .filter_map(|(k, v)| {
+                v.mixed_pressed(
+                    input,
+                    scan_codes,
+                    |v| self.keys_allowed(v),
+                    &self.mouse_buttons,
+                    &self.pressed_buttons,
+                    &self.gamepad_axis,
+                )
+                .map(|(binding, strength)| (k.clone(), binding, strength))
+            })
+            .collect::<Vec<(T, Binding, f32)>>();
+        self.raw_active.append(&mut raw_active);
+    }
+
+    /// Prunes conflicting actions according to the [`ConflictStrategy`], then updates the triggered
+    /// actions from those that remain.
+    fn update_active(
+        &mut self,
+        input: &Input<KeyCode>,
+        scan_codes: &Input<ScanCode>,
+        now: Duration,
+    ) {
+        self.update_mixed(input, scan_codes);
+        if !self.live_input {
+            self.raw_active.clear();
+            self.typed.clear();
+        }
+        self.update_simulated();
+        let replayed = self.replayed.take();
+        if let Some(frame) = &replayed {
+            for (k, strength) in &frame.active {
+                self.raw_active
+                    .push((k.clone(), Binding::default(), *strength));
+            }
+        }
+        // Restored snapshots replace the actions triggered by any other input
+        let restored = self.restored.take();
+        if let Some(state) = &restored {
+            self.raw_active = state
+                .active
+                .iter()
+                .map(|(k, strength)| (k.clone(), Binding::default(), *strength))
+                .collect();
+        }
+        self.last_update = now;
+        let raw_active = mem::take(&mut self.raw_active);
+        let mut suppressed = vec![false; raw_active.len()];
+        if !matches!(self.conflict_strategy, ConflictStrategy::AllFire) && restored.is_none() {
+            for group in self.group_conflicting(&raw_active) {
+                for &i in &group {
+                    let (action, binding, _) = &raw_active[i];
+                    for &j in &group {
+                        let (other_action, other_binding, _) = &raw_active[j];
+                        if suppressed[j]
+                            || action == other_action
+                            || self.shares_inputs(action, other_action)
+                        {
+                            continue;
+                        }
+                        if self.suppresses(action, binding, other_action, other_binding) {
+                            suppressed[j] = true;
+                        }
+                    }
                 }
             }
         }
-        let just_active = active_resolve_conflicts
+        let active_resolve_conflicts = raw_active
             .iter()
-            .filter(|v| !input_map.active.contains_key(&v.0))
-            .map(|v| (v.0.clone(), v.1.clone(), v.2))
-            .collect::<Vec<(T, Binding, f32)>>();
-        for v in just_active {
-            if v.1.keys.is_empty() || v.1.keys.iter().any(|v| input.just_pressed(*v)) {
-                input_map.just_active.insert(v.0, v.2);
+            .zip(&suppressed)
+            .filter(|v| !*v.1)
+            .map(|v| v.0)
+            .collect::<Vec<_>>();
+        for (k, binding, strength) in &active_resolve_conflicts {
+            // A canceled action whose inputs are still held restarts, eg. after a rebind
+            if !self.state.active.contains_key(k)
+                && (self.canceled.contains_key(k)
+                    || binding.just_pressed(input, scan_codes, &self.mouse_buttons))
+            {
+                self.state.just_active.insert(k.clone(), *strength);
             }
         }
+        self.active_bindings.clear();
+        for (k, binding, _) in &active_resolve_conflicts {
+            self.active_bindings
+                .entry(k.clone())
+                .or_default()
+                .push(binding.clone());
+        }
         let active = active_resolve_conflicts
             .iter()
             .map(|v| (v.0.clone(), v.2))
             .collect::<Vec<(T, f32)>>();
-        let prev_active = input_map.active.clone();
-        for k in prev_active.keys() {
+        self.events.clear();
+        for (k, strength) in mem::take(&mut self.canceled) {
+            let duration = self.elapsed(&k, now);
+            self.events.push(ActionEvent::Canceled {
+                action: k,
+                strength,
+                duration,
+                entity: None,
+            });
+        }
+        let prev_active = self.state.active.clone();
+        for (k, strength) in prev_active.iter() {
             let binding = active.iter().find(|v| v.0 == *k);
             if binding.is_none() {
-                input_map.just_inactive.insert(k.clone());
+                self.state.just_inactive.insert(k.clone());
+                let duration = self.elapsed(k, now);
+                // An action whose inputs are still held was pruned in favor of a conflicting one
+                let event = if raw_active.iter().any(|v| v.0 == *k) {
+                    ActionEvent::Canceled {
+                        action: k.clone(),
+                        strength: *strength,
+                        duration,
+                        entity: None,
+                    }
+                } else {
+                    ActionEvent::Completed {
+                        action: k.clone(),
+                        strength: *strength,
+                        duration,
+                        entity: None,
+                    }
+                };
+                self.events.push(event);
             }
         }
-        input_map.active.clear();
+        self.state.active.clear();
         for v in active {
-            input_map.active.insert(v.0, v.1);
+            self.state.active.insert(v.0, v.1);
         }
-        input_map.raw_active.clear();
+        for (k, strength) in self.state.active.clone() {
+            let event = if prev_active.contains_key(&k) {
+                ActionEvent::Ongoing {
+                    duration: now.saturating_sub(self.started[&k]),
+                    action: k,
+                    strength,
+                    entity: None,
+                }
+            } else {
+                self.started.insert(k.clone(), now);
+                ActionEvent::Started {
+                    action: k,
+                    strength,
+                    entity: None,
+                }
+            };
+            self.events.push(event);
+        }
+        // Typing a character again, eg. from key repeat, triggers its action again
+        for k in mem::take(&mut self.typed) {
+            if let Some(strength) = self.state.active.get(&k) {
+                self.state.just_active.insert(k, *strength);
+            }
+        }
+        // Replayed actions are just active on the same frames as when they were recorded
+        if let Some(frame) = replayed {
+            for (k, strength) in frame.active {
+                if frame.just_active.contains(&k) {
+                    self.state.just_active.insert(k, strength);
+                } else if !self.live_input {
+                    self.state.just_active.remove(&k);
+                }
+            }
+        }
+        // As are the transitions of restored snapshots
+        if let Some(state) = restored {
+            self.state.just_active = state.just_active;
+            self.state.just_inactive = state.just_inactive;
+        }
+        self.state.accumulate_fixed();
     }
 
-    /// System that assists in clearing the input by modifying the actual [`Input`] resource interal
-    /// to Bevy
-    fn clear_wants_clear(mut input_map: ResMut<InputMap<T>>, mut input: ResMut<Input<KeyCode>>)
+    /// Forgets when the given action became active, returning how long ago that was.
+    fn elapsed(&mut self, key: &T, now: Duration) -> Duration {
+        self.started
+            .remove(key)
+            .map(|started| now.saturating_sub(started))
+            .unwrap_or_default()
+    }
+
+    /// System that clears specifically the maps of just active or just inactive actions
+    fn clear_just_active_inactive(
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        input_map.state.clear_just_active_inactive();
+        for mut input_map in input_maps.iter_mut() {
+            input_map.state.clear_just_active_inactive();
+        }
+    }
+
+    /// System that listens to pressed [`KeyCodes`] to map to the configured actions
+    fn key_input(
+        input: Res<Input<KeyCode>>,
+        scan_codes: Res<Input<ScanCode>>,
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        input_map.update_keys(&input, &scan_codes);
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_keys(&input, &scan_codes);
+        }
+    }
+
+    /// System that listens to [`ReceivedCharacter`] events to map to the configured actions
+    fn character_input(
+        events: Option<Res<Events<ReceivedCharacter>>>,
+        mut reader: Local<ManualEventReader<ReceivedCharacter>>,
+        input: Res<Input<KeyCode>>,
+        scan_codes: Res<Input<ScanCode>>,
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        let characters = match events {
+            Some(events) => reader.iter(&events).map(|v| v.char).collect::<Vec<_>>(),
+            None => return,
+        };
+        input_map.update_characters(&characters, &input, &scan_codes);
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_characters(&characters, &input, &scan_codes);
+        }
+    }
+
+    /// System that listens to pressed [`MouseButton`]s to map to the configured actions
+    fn mouse_button_input(
+        input: Res<Input<MouseButton>>,
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        input_map.update_mouse_buttons(&input);
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_mouse_buttons(&input);
+        }
+    }
+
+    /// System that listens to [`GamepadEvent`]s to write into the raw inputs
+    fn gamepad_state(
+        mut gamepad_events: EventReader<GamepadEvent>,
+        mut input: ResMut<InputMap<T>>,
+        mut inputs: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        for event in gamepad_events.iter() {
+            input.update_gamepad(event);
+            for mut input in inputs.iter_mut() {
+                input.update_gamepad(event);
+            }
+        }
+    }
+
+    /// System that updates the gamepad button inputs in the [`InputMap`]
+    fn gamepad_button_input(
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        input_map.update_gamepad_buttons();
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_gamepad_buttons();
+        }
+    }
+
+    /// System that updates the gamepad axis inputs in the [`InputMap`]
+    fn gamepad_axis_input(
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        input_map.update_gamepad_axes();
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_gamepad_axes();
+        }
+    }
+
+    /// System that prunes conflicting actions by prioritizing that with the higher weight.
+    fn resolve_conflicts(
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+        input: Res<Input<KeyCode>>,
+        scan_codes: Res<Input<ScanCode>>,
+        time: Res<Time>,
+    ) where
+        T: 'static + Debug,
+    {
+        let now = time.time_since_startup();
+        input_map.update_active(&input, &scan_codes, now);
+        for mut input_map in input_maps.iter_mut() {
+            input_map.update_active(&input, &scan_codes, now);
+        }
+    }
+
+    /// System that copies the triggered actions of [`InputMap`] components into the
+    /// [`ActionState`] components of the same entities
+    fn sync_action_state(mut query: Query<(&InputMap<T>, &mut ActionState<T>)>)
     where
         T: 'static + Debug,
     {
-        if input_map.wants_clear {
+        for (input_map, mut state) in query.iter_mut() {
+            *state = input_map.state.clone();
+        }
+    }
+
+    /// System that sends the transitions computed by [`InputMap::resolve_conflicts`] as
+    /// [`ActionEvent`]s
+    fn send_events(
+        input_map: Res<InputMap<T>>,
+        input_maps: Query<(Entity, &InputMap<T>)>,
+        mut events: EventWriter<ActionEvent<T>>,
+    ) where
+        T: 'static + Debug,
+    {
+        events.send_batch(input_map.events.iter().cloned());
+        for (entity, input_map) in input_maps.iter() {
+            events.send_batch(
+                input_map
+                    .events
+                    .iter()
+                    .map(|event| event.clone().with_entity(entity)),
+            );
+        }
+    }
+
+    /// System that assists in clearing the input by modifying the actual [`Input`] resources interal
+    /// to Bevy
+    fn clear_wants_clear(
+        mut input_map: ResMut<InputMap<T>>,
+        mut input_maps: Query<&mut InputMap<T>>,
+        mut input: ResMut<Input<KeyCode>>,
+        mut scan_codes: ResMut<Input<ScanCode>>,
+    ) where
+        T: 'static + Debug,
+    {
+        let mut wants_clear = input_map.wants_clear;
+        for mut input_map in input_maps.iter_mut() {
+            wants_clear |= input_map.wants_clear;
+            input_map.wants_clear = false;
+        }
+        if wants_clear {
             input.clear();
             let mut v = vec![];
             for i in input.get_pressed().cloned() {
@@ -547,65 +1445,494 @@ where
             for i in v {
                 input.reset(i);
             }
+            scan_codes.clear();
+            let mut v = vec![];
+            for i in scan_codes.get_pressed().cloned() {
+                v.push(i);
+            }
+            for i in v {
+                scan_codes.reset(i);
+            }
         }
 
         input_map.wants_clear = false;
     }
 }
 
-pub struct ActionPlugin<'a, T>(std::marker::PhantomData<&'a T>);
+/// Labels for the systems added by [`ActionPlugin`], for ordering application systems relative to
+/// action resolution.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemLabel)]
+pub enum ActionSystem {
+    /// Clears the just active and just inactive actions at the start of the frame.
+    ClearTransitions,
+    /// Reads keyboard and mouse input and [`GamepadEvent`]s into each [`InputMap`].
+    UpdateStates,
+    /// Maps gamepad buttons and axes to actions.
+    MapGamepad,
+    /// Resolves conflicting bindings and updates the triggered actions of each [`InputMap`].
+    ResolveConflicts,
+    /// Copies triggered actions into [`ActionState`] components.
+    SyncState,
+    /// Sends [`ActionEvent`]s if enabled with [`ActionPlugin::with_events`].
+    SendEvents,
+    /// Captures input for the [`Rebinder`].
+    Rebind,
+    /// Feeds the next frame of the [`ActionPlayer`] to the [`InputMap`] resource.
+    Replay,
+    /// Records the actions resolved this frame for the [`ActionRecorder`].
+    Record,
+    /// Resets the [`Input`] resource after a call to [`InputMap::clear`].
+    ClearInput,
+}
+
+pub struct ActionPlugin<'a, T> {
+    send_events: bool,
+    first_stage: Box<dyn StageLabel>,
+    update_stage: Box<dyn StageLabel>,
+    last_stage: Box<dyn StageLabel>,
+    conflict_strategy: Option<ConflictStrategy<T>>,
+    chord_order: Option<ChordOrder>,
+    marker: std::marker::PhantomData<&'a T>,
+}
 
 impl<'a, T> Default for ActionPlugin<'a, T> {
     fn default() -> Self {
-        Self(std::marker::PhantomData)
+        Self {
+            send_events: false,
+            first_stage: Box::new(CoreStage::First),
+            update_stage: Box::new(CoreStage::PreUpdate),
+            last_stage: Box::new(CoreStage::PostUpdate),
+            conflict_strategy: None,
+            chord_order: None,
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ActionPlugin<'a, T> {
+    /// Sends an [`ActionEvent`] for every action transition in addition to updating the
+    /// [`InputMap`].
+    pub fn with_events(mut self) -> Self {
+        self.send_events = true;
+        self
+    }
+
+    /// Runs the plugin's systems in the given stages rather than [`CoreStage::First`],
+    /// [`CoreStage::PreUpdate`] and [`CoreStage::PostUpdate`]. Just active and just inactive
+    /// actions are cleared in `first`, actions are resolved in `update`, and the [`Input`] resource
+    /// is reset in `last` after a call to [`InputMap::clear`]. The same stage may be given more than
+    /// once, in which case the systems still run in that order.
+    pub fn with_stages(
+        mut self,
+        first: impl StageLabel,
+        update: impl StageLabel,
+        last: impl StageLabel,
+    ) -> Self {
+        self.first_stage = Box::new(first);
+        self.update_stage = Box::new(update);
+        self.last_stage = Box::new(last);
+        self
     }
+
+    /// Resolves conflicts between bindings of the [`InputMap`] resource with the given strategy
+    /// rather than [`ConflictStrategy::MostSpecific`].
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy<T>) -> Self {
+        self.conflict_strategy = Some(strategy);
+        self
+    }
+
+    /// Triggers keyboard chords of the [`InputMap`] resource depending on the order their keys
+    /// were pressed in, rather than in [`ChordOrder::AnyOrder`].
+    pub fn with_chord_order(mut self, order: ChordOrder) -> Self {
+        self.chord_order = Some(order);
+        self
+    }
+}
+
+fn system_stage<'a>(app: &'a mut App, label: &dyn StageLabel) -> &'a mut SystemStage {
+    app.schedule
+        .get_stage_mut::<SystemStage>(label)
+        .unwrap_or_else(|| panic!("Stage '{:?}' does not exist or is not a SystemStage", label))
 }
 
 impl<T> Plugin for ActionPlugin<'static, T>
 where
     InputMap<T>: Default,
-    T: Hash + Eq + Clone + Send + Sync + Debug,
+    T: 'static + Hash + Eq + Clone + Send + Sync + Debug,
 {
     fn build(&self, app: &mut App) {
-        const UPDATE_STATES_LABEL: &str = "UPDATE_STAES";
-        const RESOLVE_CONFLICTS_LABEL: &str = "RESOLVE_CONFLICTS";
         app.init_resource::<InputMap<T>>()
-            // Clear the `just_active` and `just_inactive` maps at the start of every iteration of the
-            // application's main loop to ensure that there are no false positives
-            .add_system_to_stage(CoreStage::First, InputMap::<T>::clear_just_active_inactive)
-            // Register keyboard input
-            .add_system_to_stage(
-                CoreStage::PreUpdate,
-                InputMap::<T>::key_input
-                    .label(UPDATE_STATES_LABEL)
-                    .after(InputSystem),
+            .init_resource::<Rebinder<T>>()
+            .init_resource::<ActionRecorder<T>>()
+            .init_resource::<ActionPlayer<T>>()
+            .add_event::<RebindEvent<T>>();
+        if let Some(strategy) = &self.conflict_strategy {
+            app.world
+                .resource_mut::<InputMap<T>>()
+                .set_conflict_strategy(strategy.clone());
+        }
+        if let Some(order) = self.chord_order {
+            app.world
+                .resource_mut::<InputMap<T>>()
+                .set_chord_order(order);
+        }
+        if self.send_events {
+            app.add_event::<ActionEvent<T>>();
+        }
+        // Clear the `just_active` and `just_inactive` maps at the start of every iteration of the
+        // application's main loop to ensure that there are no false positives
+        let mut clear_transitions =
+            InputMap::<T>::clear_just_active_inactive.label(ActionSystem::ClearTransitions);
+        if *self.first_stage == *self.update_stage {
+            clear_transitions = clear_transitions.before(ActionSystem::UpdateStates);
+        }
+        system_stage(app, &*self.first_stage).add_system(clear_transitions);
+        // Register keyboard and gamepad inputs, after Bevy has updated its own if in the same stage
+        let mut update_states = SystemSet::new()
+            .label(ActionSystem::UpdateStates)
+            .with_system(InputMap::<T>::key_input)
+            .with_system(InputMap::<T>::mouse_button_input)
+            .with_system(InputMap::<T>::character_input)
+            .with_system(InputMap::<T>::gamepad_state);
+        if *self.update_stage == *(&CoreStage::PreUpdate as &dyn StageLabel) {
+            update_states = update_states.after(InputSystem);
+        }
+        // Scan codes are tracked once, however many action types are registered
+        let track_scan_codes = !app.world.contains_resource::<Input<ScanCode>>();
+        if track_scan_codes {
+            app.init_resource::<Input<ScanCode>>()
+                .init_resource::<KeyboardLayout>();
+        }
+        let update = system_stage(app, &*self.update_stage);
+        if track_scan_codes {
+            update.add_system(KeyboardLayout::scan_code_input.before(ActionSystem::UpdateStates));
+        }
+        update
+            .add_system_set(update_states)
+            // Then map those gamepad inputs to the correct actions
+            .add_system_set(
+                SystemSet::new()
+                    .label(ActionSystem::MapGamepad)
+                    .after(ActionSystem::UpdateStates)
+                    .with_system(InputMap::<T>::gamepad_button_input)
+                    .with_system(InputMap::<T>::gamepad_axis_input),
             )
-            // Register gamepad inputs
-            .add_system_to_stage(
-                CoreStage::PreUpdate,
-                InputMap::<T>::gamepad_state
-                    .label(UPDATE_STATES_LABEL)
-                    .after(InputSystem),
+            // Resolve all conflicts based on weight
+            .add_system(
+                InputMap::<T>::resolve_conflicts
+                    .label(ActionSystem::ResolveConflicts)
+                    .after(ActionSystem::UpdateStates)
+                    .after(ActionSystem::MapGamepad),
             )
-            // Then map those gamepad inputs to the correct actions
-            .add_system_to_stage(
-                CoreStage::PreUpdate,
-                InputMap::<T>::gamepad_button_input
-                    .after(UPDATE_STATES_LABEL)
-                    .before(RESOLVE_CONFLICTS_LABEL),
+            // Copy the results into any `ActionState` components
+            .add_system(
+                InputMap::<T>::sync_action_state
+                    .label(ActionSystem::SyncState)
+                    .after(ActionSystem::ResolveConflicts),
             )
-            .add_system_to_stage(
-                CoreStage::PreUpdate,
-                InputMap::<T>::gamepad_axis_input
-                    .after(UPDATE_STATES_LABEL)
-                    .before(RESOLVE_CONFLICTS_LABEL),
+            // Replay recorded actions in place of, or alongside, those of live input
+            .add_system(
+                ActionPlayer::<T>::replay
+                    .label(ActionSystem::Replay)
+                    .before(ActionSystem::ResolveConflicts),
             )
-            // Resolve all conflicts based on weight
-            .add_system_to_stage(
-                CoreStage::PreUpdate,
-                InputMap::<T>::resolve_conflicts.label(RESOLVE_CONFLICTS_LABEL),
+            .add_system(
+                ActionRecorder::<T>::record
+                    .label(ActionSystem::Record)
+                    .after(ActionSystem::ResolveConflicts),
             )
-            // And clear the inputs if requested
-            .add_system_to_stage(CoreStage::PostUpdate, InputMap::<T>::clear_wants_clear);
+            // Capture input for rebinding once actions are resolved, so that a binding applied
+            // this frame takes effect on the next one
+            .add_system(
+                Rebinder::<T>::capture
+                    .label(ActionSystem::Rebind)
+                    .after(ActionSystem::ResolveConflicts),
+            );
+        if self.send_events {
+            update.add_system(
+                InputMap::<T>::send_events
+                    .label(ActionSystem::SendEvents)
+                    .after(ActionSystem::ResolveConflicts),
+            );
+        }
+        // And clear the inputs if requested, once they have been read
+        let mut clear_input = InputMap::<T>::clear_wants_clear.label(ActionSystem::ClearInput);
+        if *self.last_stage == *self.update_stage {
+            clear_input = clear_input
+                .after(ActionSystem::ResolveConflicts)
+                .after(ActionSystem::Rebind);
+        }
+        system_stage(app, &*self.last_stage).add_system(clear_input);
     }
 }
+
+#[test]
+fn test_apply_bindings() {
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .bind("fire", KeyCode::LControl);
+    let gamepad = Gamepad(0);
+    map.gamepads.insert(gamepad);
+    let mut input = Input::<KeyCode>::default();
+    input.press(KeyCode::Space);
+    input.press(KeyCode::LControl);
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.active("jump") && map.active("fire"));
+
+    let mut other = InputMap::<String>::default();
+    other
+        .bind("jump", KeyCode::W)
+        .bind("fire", KeyCode::LControl);
+    map.apply_bindings(other);
+    assert!(map.just_inactive("jump"));
+    assert!(map.active("fire"));
+    assert!(map.gamepads.contains(&gamepad));
+
+    input.clear();
+    map.state.clear_just_active_inactive();
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::from_secs(1));
+    assert!(!map.active("jump"));
+    assert!(!map.just_active("fire"));
+    assert!(map.events.iter().any(|v| matches!(
+        v,
+        ActionEvent::Canceled { action, .. } if action == "jump"
+    )));
+
+    // Rebinding while both the old and the new inputs are held restarts the action
+    input.release(KeyCode::LControl);
+    input.press(KeyCode::W);
+    input.press(KeyCode::Space);
+    map.state.clear_just_active_inactive();
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::from_secs(2));
+    assert!(map.just_active("jump"));
+    let mut other = InputMap::<String>::default();
+    other.bind("jump", KeyCode::Space);
+    map.apply_bindings(other);
+    assert!(map.just_inactive("jump"));
+    input.clear();
+    map.state.clear_just_active_inactive();
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::from_secs(3));
+    assert!(map.just_active("jump"));
+    assert_eq!(
+        map.events,
+        vec![
+            ActionEvent::Canceled {
+                action: "jump".to_string(),
+                strength: 1.,
+                duration: Duration::from_secs(1),
+                entity: None,
+            },
+            ActionEvent::Started {
+                action: "jump".to_string(),
+                strength: 1.,
+                entity: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_characters() {
+    let mut map = InputMap::<String>::default();
+    map.bind("help", '?');
+    let input = Input::<KeyCode>::default();
+    map.update_characters(&['?'], &input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("help"));
+    // Repeated while held
+    map.state.clear_just_active_inactive();
+    map.update_characters(&['?'], &input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("help"));
+    map.state.clear_just_active_inactive();
+    map.set_text_entry(true)
+        .update_characters(&['?'], &input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_inactive("help"));
+
+    // With a modifier, only typed while it is held
+    let mut map = InputMap::<String>::default();
+    let binding = Binding::from('z').with_modifier(Modifier::Control);
+    map.bind("undo", binding.clone()).bind("z", 'z');
+    let mut input = Input::<KeyCode>::default();
+    input.press(KeyCode::Z);
+    map.update_keys(&input, &default());
+    map.update_characters(&['z'], &input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("z") && !map.active("undo"));
+    map.state.clear_just_active_inactive();
+    input.press(KeyCode::LControl);
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_inactive("z") && !map.active("undo"));
+    map.update_keys(&input, &default());
+    map.update_characters(&['z'], &input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("undo") && !map.active("z"));
+}
+
+#[test]
+fn test_text_entry() {
+    let mut map = InputMap::<String>::default();
+    map.bind("up", KeyCode::W)
+        .bind("up", GamepadButtonType::DPadUp)
+        .bind("fire", MouseButton::Left)
+        .bind("close", KeyCode::Escape);
+    let mut input = Input::<KeyCode>::default();
+    let mut mouse = Input::<MouseButton>::default();
+    input.press(KeyCode::W);
+    mouse.press(MouseButton::Left);
+    map.update_keys(&input, &default());
+    map.update_mouse_buttons(&mouse);
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.active("up") && map.active("fire"));
+
+    map.set_text_entry(true);
+    assert!(map.just_inactive("up"));
+    assert!(map.active("fire"));
+    map.state.clear_just_active_inactive();
+    input.press(KeyCode::Escape);
+    map.update_keys(&input, &default());
+    map.update_mouse_buttons(&mouse);
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(!map.active("up"));
+    assert!(map.active("fire"));
+    assert!(map.just_active("close"));
+}
+
+#[test]
+fn test_mixed_conflicts() {
+    let mut map = InputMap::<String>::default();
+    map.bind("steer", GamepadAxisDirection::LeftStickXPositive)
+        .bind(
+            "drift",
+            "Gamepad:LeftTrigger+LeftStickX+"
+                .parse::<Binding>()
+                .unwrap(),
+        )
+        .bind("fire", MouseButton::Left)
+        .bind("alt_fire", "Shift+Mouse:Left".parse::<Binding>().unwrap());
+    map.update_gamepad(&GamepadEvent(
+        Gamepad(0),
+        GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, 0.5),
+    ));
+    map.update_gamepad_buttons();
+    map.update_gamepad_axes();
+    map.update_active(&default(), &default(), Duration::ZERO);
+    assert!(map.active("steer"));
+    assert!(!map.active("drift"));
+
+    map.update_gamepad(&GamepadEvent(
+        Gamepad(0),
+        GamepadEventType::ButtonChanged(GamepadButtonType::LeftTrigger, 1.),
+    ));
+    map.update_gamepad_buttons();
+    map.update_gamepad_axes();
+    map.update_active(&default(), &default(), Duration::ZERO);
+    assert!(!map.active("steer"));
+    assert_eq!(map.strength("drift"), 0.5);
+
+    let mut input = Input::<KeyCode>::default();
+    let mut mouse = Input::<MouseButton>::default();
+    input.press(KeyCode::LShift);
+    map.state.clear_just_active_inactive();
+    map.update_keys(&input, &default());
+    map.update_mouse_buttons(&mouse);
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(!map.active("alt_fire"));
+    input.clear();
+    mouse.press(MouseButton::Left);
+    map.update_keys(&input, &default());
+    map.update_mouse_buttons(&mouse);
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("alt_fire"));
+    assert!(!map.active("fire"));
+}
+
+#[test]
+fn test_components() {
+    use bevy::input::InputPlugin;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin::<String>::default().with_events());
+    let players = [Gamepad(0), Gamepad(1)].map(|gamepad| {
+        let mut map = InputMap::<String>::default();
+        map.bind("jump", GamepadButtonType::South)
+            .bind("pause", KeyCode::Escape)
+            .set_gamepad(Some(gamepad));
+        app.send_gamepad_event(gamepad, GamepadEventType::Connected);
+        app.world
+            .spawn()
+            .insert(map)
+            .insert(ActionState::<String>::default())
+            .id()
+    });
+    app.update();
+    app.set_gamepad_button(Gamepad(1), GamepadButtonType::South, 1.)
+        .update();
+    let state = |app: &App, player| {
+        app.world
+            .get::<ActionState<String>>(player)
+            .unwrap()
+            .clone()
+    };
+    assert!(!state(&app, players[0]).active("jump"));
+    assert!(state(&app, players[1]).just_active("jump"));
+
+    // Keyboard input reaches every map
+    app.press_key(KeyCode::Escape).update();
+    assert!(state(&app, players[0]).just_active("pause"));
+    assert!(state(&app, players[1]).just_active("pause"));
+    assert!(!state(&app, players[1]).just_active("jump"));
+
+    // Events carry the entity of their map
+    let events = app.world.resource::<Events<ActionEvent<String>>>();
+    let started = events
+        .get_reader()
+        .iter(events)
+        .filter(|v| matches!(v, ActionEvent::Started { action, .. } if action == "pause"))
+        .map(ActionEvent::entity)
+        .collect::<Vec<_>>();
+    assert_eq!(started.len(), 2);
+    assert!(players.iter().all(|v| started.contains(&Some(*v))));
+}
+
+#[test]
+fn test_single_stage() {
+    use bevy::input::InputPlugin;
+
+    #[derive(Clone, Debug, Eq, Hash, PartialEq, StageLabel)]
+    struct Actions;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_stage_after(CoreStage::Update, Actions, SystemStage::parallel())
+        .add_plugin(ActionPlugin::<String>::default().with_stages(Actions, Actions, Actions));
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("jump", KeyCode::Space);
+    let map = |app: &App| app.world.resource::<InputMap<String>>().state().clone();
+    for _ in 0..10 {
+        app.press_key(KeyCode::Space).update();
+        assert!(map(&app).just_active("jump"));
+        app.update();
+        assert!(map(&app).active("jump") && !map(&app).just_active("jump"));
+        app.release_key(KeyCode::Space).update();
+        assert!(map(&app).just_inactive("jump"));
+    }
+
+    // The held key is reset once the actions are resolved, so it no longer counts from the next
+    // frame on
+    app.press_key(KeyCode::Space).update();
+    app.world.resource_mut::<InputMap<String>>().clear();
+    app.update();
+    app.update();
+    assert!(!map(&app).active("jump"));
+}
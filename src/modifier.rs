@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A modifier key matched by either of its physical keys, eg. `Control` is held when either
+/// `LControl` or `RControl` is.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Modifier {
+    Shift,
+    Control,
+    Alt,
+    /// The Windows key, or Command on macOS.
+    Super,
+    /// The modifier used for application shortcuts, such as copy and paste. Command on macOS,
+    /// otherwise Control.
+    ///
+    /// The platform is chosen when compiling, so web builds use Control even when run in a browser
+    /// on macOS.
+    Primary,
+}
+
+impl Modifier {
+    /// Returns the physical keys that hold this modifier on the current platform.
+    pub fn keys(&self) -> [KeyCode; 2] {
+        match self {
+            Modifier::Shift => [KeyCode::LShift, KeyCode::RShift],
+            Modifier::Control => [KeyCode::LControl, KeyCode::RControl],
+            Modifier::Alt => [KeyCode::LAlt, KeyCode::RAlt],
+            Modifier::Super => [KeyCode::LWin, KeyCode::RWin],
+            Modifier::Primary if cfg!(target_os = "macos") => Modifier::Super.keys(),
+            Modifier::Primary => Modifier::Control.keys(),
+        }
+    }
+
+    /// Returns whether the given key holds this modifier.
+    pub fn matches(&self, key: KeyCode) -> bool {
+        self.keys().contains(&key)
+    }
+
+    /// Returns whether this modifier is held.
+    pub fn pressed(&self, input: &Input<KeyCode>) -> bool {
+        input.any_pressed(self.keys())
+    }
+
+    /// Returns whether this modifier was just held, and wasn't already held by its other key.
+    pub(crate) fn just_pressed(&self, input: &Input<KeyCode>) -> bool {
+        input.any_just_pressed(self.keys())
+            && !self
+                .keys()
+                .iter()
+                .any(|v| input.pressed(*v) && !input.just_pressed(*v))
+    }
+
+    /// Returns whether both modifiers are held by the same keys, eg. `Primary` and `Control` on
+    /// platforms other than macOS.
+    pub(crate) fn aliases(&self, other: &Modifier) -> bool {
+        self.keys().iter().any(|v| other.matches(*v))
+    }
+}
+
+/// Returns whether the given key holds any modifier.
+pub(crate) fn is_modifier_key(key: KeyCode) -> bool {
+    [
+        Modifier::Shift,
+        Modifier::Control,
+        Modifier::Alt,
+        Modifier::Super,
+    ]
+    .iter()
+    .any(|v| v.matches(key))
+}
+
+#[test]
+fn test_modifiers() {
+    use std::time::Duration;
+
+    use crate::{Binding, ConflictKind, InputMap};
+
+    let mut map = InputMap::<String>::default();
+    map.bind(
+        "save",
+        Binding::from(KeyCode::S).with_modifier(Modifier::Control),
+    )
+    .bind("back", KeyCode::S);
+    let mut input = Input::<KeyCode>::default();
+    input.press(KeyCode::RControl);
+    input.press(KeyCode::S);
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("save"));
+    assert!(!map.active("back"));
+
+    let conflicts = map.conflicts_with("other", vec![KeyCode::LControl, KeyCode::S]);
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts
+        .iter()
+        .any(|v| v.other_action == "save" && v.kind == ConflictKind::Identical));
+    assert!(conflicts
+        .iter()
+        .any(|v| v.other_action == "back" && v.kind == ConflictKind::Shadows));
+}
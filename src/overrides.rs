@@ -0,0 +1,158 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Action, InputMap};
+
+/// The actions of an [`InputMap`] whose bindings differ from its defaults, as returned by
+/// [`InputMap::overrides`]. With the `serialize` feature, this is what should be saved for the
+/// player, so that actions added or rebound in later versions pick up their new defaults.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(
+        transparent,
+        bound(
+            serialize = "T: Serialize + Eq + Hash",
+            deserialize = "T: Deserialize<'de> + Eq + Hash"
+        )
+    )
+)]
+pub struct BindingOverrides<T> {
+    pub(crate) actions: HashMap<T, Action>,
+}
+
+impl<T> Default for BindingOverrides<T> {
+    fn default() -> Self {
+        Self {
+            actions: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PartialEq for BindingOverrides<T>
+where
+    T: Hash + Eq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.actions == other.actions
+    }
+}
+
+impl<T> BindingOverrides<T>
+where
+    T: Hash + Eq,
+{
+    /// Returns whether no action differs from its defaults.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Iterates over the overridden actions. An action without bindings was unbound by the player.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &Action)> {
+        self.actions.iter()
+    }
+}
+
+impl<T> InputMap<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Records the current bindings as the defaults that [`InputMap::reset_to_default`] restores
+    /// and [`InputMap::overrides`] compares against. Call this once default bindings are set up
+    /// in code, before applying the player's overrides.
+    pub fn set_defaults(&mut self) -> &mut Self {
+        self.defaults = self.actions.clone();
+        self
+    }
+
+    /// Returns whether the given action has its default bindings and priority.
+    pub fn is_default<K: Into<T>>(&self, action: K) -> bool {
+        let action = action.into();
+        let unbound = Action::default();
+        self.actions.get(&action).unwrap_or(&unbound)
+            == self.defaults.get(&action).unwrap_or(&unbound)
+    }
+
+    /// Restores the default bindings and priority of the given action.
+    pub fn reset_to_default<K: Into<T>>(&mut self, action: K) -> &mut Self {
+        let action = action.into();
+        if !self.is_default(action.clone()) {
+            self.cancel(&action);
+        }
+        if let Some(default) = self.defaults.get(&action) {
+            self.actions.insert(action, default.clone());
+        } else {
+            self.actions.remove(&action);
+        }
+        self.conflict_groups = None;
+        self
+    }
+
+    /// Restores the default bindings of every action.
+    pub fn reset_all_to_default(&mut self) -> &mut Self {
+        self.set_actions(self.defaults.clone());
+        self
+    }
+
+    /// Returns the actions whose bindings or priority differ from the defaults.
+    pub fn overrides(&self) -> BindingOverrides<T> {
+        let mut actions = HashMap::new();
+        for (k, v) in &self.actions {
+            if !self.is_default(k.clone()) {
+                actions.insert(k.clone(), v.clone());
+            }
+        }
+        for k in self.defaults.keys() {
+            if !self.actions.contains_key(k) {
+                actions.insert(k.clone(), Action::default());
+            }
+        }
+        BindingOverrides { actions }
+    }
+
+    /// Restores the default bindings of every action, then replaces the bindings of those in the
+    /// given overrides.
+    pub fn apply_overrides(&mut self, overrides: &BindingOverrides<T>) -> &mut Self {
+        let mut actions = self.defaults.clone();
+        for (k, v) in &overrides.actions {
+            actions.insert(k.clone(), v.clone());
+        }
+        self.set_actions(actions);
+        self
+    }
+}
+
+#[test]
+fn test_overrides() {
+    use bevy::prelude::KeyCode;
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .bind("fire", KeyCode::LControl)
+        .set_defaults();
+    assert!(map.overrides().is_empty());
+    map.unbind_all("jump").bind("jump", KeyCode::W);
+    map.remove_action("fire");
+    let overrides = map.overrides();
+    assert_eq!(overrides.iter().count(), 2);
+    map.reset_to_default("fire");
+    assert!(map.is_default("fire"));
+    assert!(!map.is_default("jump"));
+    map.bind("crouch", KeyCode::C).reset_all_to_default();
+    assert!(map.overrides().is_empty());
+    map.apply_overrides(&overrides);
+    assert_eq!(map.overrides(), overrides);
+    assert!(map.bindings("fire").is_empty());
+
+    map.reset_all_to_default().set_priority("fire", 1);
+    assert!(!map.is_default("fire"));
+    let overrides = map.overrides();
+    assert_eq!(overrides.iter().next().unwrap().1.priority(), 1);
+    map.reset_to_default("fire");
+    assert!(map.overrides().is_empty());
+    map.apply_overrides(&overrides);
+    assert_eq!(map.priority("fire"), 1);
+}
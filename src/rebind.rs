@@ -0,0 +1,497 @@
+use std::{collections::HashSet, hash::Hash, mem, time::Duration};
+
+use bevy::{
+    input::gamepad::{GamepadAxis, GamepadButton, Gamepads},
+    prelude::*,
+};
+
+use crate::{Binding, GamepadAxisDirection, InputMap};
+
+const AXES: [GamepadAxisType; 6] = [
+    GamepadAxisType::LeftStickX,
+    GamepadAxisType::LeftStickY,
+    GamepadAxisType::RightStickX,
+    GamepadAxisType::RightStickY,
+    GamepadAxisType::DPadX,
+    GamepadAxisType::DPadY,
+];
+
+/// The kinds of input a [`Rebind`] may capture.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RebindDevices {
+    pub keyboard: bool,
+    pub mouse: bool,
+    pub gamepad_buttons: bool,
+    pub gamepad_axes: bool,
+}
+
+impl Default for RebindDevices {
+    fn default() -> Self {
+        Self {
+            keyboard: true,
+            mouse: true,
+            gamepad_buttons: true,
+            gamepad_axes: true,
+        }
+    }
+}
+
+/// What a [`Rebind`] does with the binding it captures besides reporting it
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Apply {
+    None,
+    Append,
+    Replace(usize),
+}
+
+/// A request to capture the next chord the player completes and build a [`Binding`] from it.
+#[derive(Clone, Debug)]
+pub struct Rebind<T> {
+    action: T,
+    timeout: Option<Duration>,
+    cancel: Option<KeyCode>,
+    devices: RebindDevices,
+    axis_threshold: f32,
+    apply: Apply,
+}
+
+impl<T> Rebind<T> {
+    /// Creates a request to rebind the given action, which can be canceled with
+    /// [`KeyCode::Escape`] and never times out.
+    pub fn new<K: Into<T>>(action: K) -> Self {
+        Self {
+            action: action.into(),
+            timeout: None,
+            cancel: Some(KeyCode::Escape),
+            devices: default(),
+            axis_threshold: 0.5,
+            apply: Apply::None,
+        }
+    }
+
+    /// Gives up on capturing input after the given duration.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Uses the given key to cancel the rebind, or no key at all if None. The cancel key is never
+    /// captured.
+    pub fn with_cancel_key(mut self, key: Option<KeyCode>) -> Self {
+        self.cancel = key;
+        self
+    }
+
+    /// Only captures input from the given devices.
+    pub fn with_devices(mut self, devices: RebindDevices) -> Self {
+        self.devices = devices;
+        self
+    }
+
+    /// Sets how far a gamepad axis must be pushed before it is captured.
+    pub fn with_axis_threshold(mut self, threshold: f32) -> Self {
+        self.axis_threshold = threshold;
+        self
+    }
+
+    /// Adds the captured binding to the action in the [`InputMap`] resource as well as reporting it.
+    pub fn apply(mut self) -> Self {
+        self.apply = Apply::Append;
+        self
+    }
+
+    /// Replaces the action's binding at the given index in the [`InputMap`] resource with the
+    /// captured binding as well as reporting it. See [`InputMap::replace_binding`]. Should the
+    /// action not have a binding at that index, the captured binding is added as with
+    /// [`Rebind::apply`].
+    pub fn replace(mut self, index: usize) -> Self {
+        self.apply = Apply::Replace(index);
+        self
+    }
+}
+
+/// The outcome of a [`Rebind`].
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum RebindResult {
+    /// The player completed a chord, from which this binding was built.
+    Bound(Binding),
+    /// The player pressed the cancel key or [`Rebinder::cancel`] was called.
+    Canceled,
+    /// No chord was completed before the timeout elapsed.
+    TimedOut,
+}
+
+/// An event sent when a [`Rebind`] finishes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebindEvent<T> {
+    pub action: T,
+    pub result: RebindResult,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Chord {
+    keys: HashSet<KeyCode>,
+    mouse_buttons: HashSet<MouseButton>,
+    gamepad_buttons: HashSet<GamepadButtonType>,
+    gamepad_axis_directions: HashSet<GamepadAxisDirection>,
+}
+
+impl Chord {
+    fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+            && self.mouse_buttons.is_empty()
+            && self.gamepad_buttons.is_empty()
+            && self.gamepad_axis_directions.is_empty()
+    }
+
+    fn extend(&mut self, other: &Chord) {
+        self.keys.extend(other.keys.iter().cloned());
+        self.mouse_buttons
+            .extend(other.mouse_buttons.iter().cloned());
+        self.gamepad_buttons
+            .extend(other.gamepad_buttons.iter().cloned());
+        self.gamepad_axis_directions
+            .extend(other.gamepad_axis_directions.iter().cloned());
+    }
+
+    fn retain_held(&mut self, held: &Chord) {
+        self.keys.retain(|v| held.keys.contains(v));
+        self.mouse_buttons
+            .retain(|v| held.mouse_buttons.contains(v));
+        self.gamepad_buttons
+            .retain(|v| held.gamepad_buttons.contains(v));
+        self.gamepad_axis_directions
+            .retain(|v| held.gamepad_axis_directions.contains(v));
+    }
+
+    fn without(mut self, ignored: &Chord) -> Self {
+        self.keys.retain(|v| !ignored.keys.contains(v));
+        self.mouse_buttons
+            .retain(|v| !ignored.mouse_buttons.contains(v));
+        self.gamepad_buttons
+            .retain(|v| !ignored.gamepad_buttons.contains(v));
+        self.gamepad_axis_directions
+            .retain(|v| !ignored.gamepad_axis_directions.contains(v));
+        self
+    }
+}
+
+impl From<Chord> for Binding {
+    fn from(chord: Chord) -> Self {
+        Self {
+            keys: chord.keys,
+            mouse_buttons: chord.mouse_buttons,
+            gamepad_buttons: chord.gamepad_buttons,
+            gamepad_axis_directions: chord.gamepad_axis_directions,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Capture<T> {
+    rebind: Rebind<T>,
+    started: Option<Duration>,
+    ignored: Option<Chord>,
+    chord: Chord,
+}
+
+/// A Bevy resource that captures the next chord the player completes for an action, eg. for a
+/// "press a key for Jump" prompt. Results are sent as [`RebindEvent`]s.
+#[derive(Debug)]
+pub struct Rebinder<T> {
+    capture: Option<Capture<T>>,
+    canceled: Option<T>,
+}
+
+impl<T> Default for Rebinder<T> {
+    fn default() -> Self {
+        Self {
+            capture: None,
+            canceled: None,
+        }
+    }
+}
+
+impl<T> Rebinder<T>
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    /// Starts capturing input for the given [`Rebind`], canceling any capture in progress. Inputs
+    /// already held when the capture starts are ignored until released.
+    pub fn start(&mut self, rebind: Rebind<T>) {
+        self.cancel();
+        self.capture = Some(Capture {
+            rebind,
+            started: None,
+            ignored: None,
+            chord: default(),
+        });
+    }
+
+    /// Cancels the capture in progress, if any.
+    pub fn cancel(&mut self) {
+        if let Some(capture) = self.capture.take() {
+            self.canceled = Some(capture.rebind.action);
+        }
+    }
+
+    /// Returns the action input is currently being captured for.
+    pub fn capturing(&self) -> Option<&T> {
+        self.capture.as_ref().map(|v| &v.rebind.action)
+    }
+
+    /// Collects the inputs currently held on the devices allowed by the capture
+    fn held(
+        rebind: &Rebind<T>,
+        keys: &Input<KeyCode>,
+        mouse_buttons: &Input<MouseButton>,
+        gamepads: &Gamepads,
+        gamepad_buttons: &Input<GamepadButton>,
+        gamepad_axes: &Axis<GamepadAxis>,
+    ) -> Chord {
+        let mut chord = Chord::default();
+        if rebind.devices.keyboard {
+            chord.keys = keys
+                .get_pressed()
+                .filter(|v| Some(**v) != rebind.cancel)
+                .cloned()
+                .collect();
+        }
+        if rebind.devices.mouse {
+            chord.mouse_buttons = mouse_buttons.get_pressed().cloned().collect();
+        }
+        if rebind.devices.gamepad_buttons {
+            chord.gamepad_buttons = gamepad_buttons.get_pressed().map(|v| v.1).collect();
+        }
+        if rebind.devices.gamepad_axes {
+            for gamepad in gamepads.iter() {
+                for axis_type in AXES {
+                    let value = gamepad_axes
+                        .get(GamepadAxis(*gamepad, axis_type))
+                        .unwrap_or_default();
+                    if value.abs() > rebind.axis_threshold {
+                        if let Some((direction, _)) =
+                            GamepadAxisDirection::from_axis(axis_type, value >= 0.)
+                        {
+                            chord.gamepad_axis_directions.insert(direction);
+                        }
+                    }
+                }
+            }
+        }
+        chord
+    }
+
+    /// System that captures input for the [`Rebinder`] and reports the result
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn capture(
+        mut rebinder: ResMut<Rebinder<T>>,
+        mut input_map: ResMut<InputMap<T>>,
+        mut events: EventWriter<RebindEvent<T>>,
+        time: Res<Time>,
+        keys: Res<Input<KeyCode>>,
+        mouse_buttons: Res<Input<MouseButton>>,
+        gamepads: Res<Gamepads>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+        gamepad_axes: Res<Axis<GamepadAxis>>,
+    ) {
+        if let Some(action) = rebinder.canceled.take() {
+            events.send(RebindEvent {
+                action,
+                result: RebindResult::Canceled,
+            });
+        }
+        let now = time.time_since_startup();
+        let result = if let Some(capture) = rebinder.capture.as_mut() {
+            let held = Self::held(
+                &capture.rebind,
+                &keys,
+                &mouse_buttons,
+                &gamepads,
+                &gamepad_buttons,
+                &gamepad_axes,
+            );
+            let started = *capture.started.get_or_insert(now);
+            // Inputs pressed since the capture started aren't ignored, even on its first frame
+            let ignored = capture.ignored.get_or_insert_with(|| {
+                let mut ignored = held.clone();
+                ignored.keys.retain(|v| !keys.just_pressed(*v));
+                ignored
+                    .mouse_buttons
+                    .retain(|v| !mouse_buttons.just_pressed(*v));
+                ignored
+                    .gamepad_buttons
+                    .retain(|v| !gamepad_buttons.get_just_pressed().any(|b| b.1 == *v));
+                ignored
+            });
+            ignored.retain_held(&held);
+            let held = held.without(ignored);
+            if capture
+                .rebind
+                .cancel
+                .is_some_and(|key| keys.just_pressed(key))
+            {
+                Some(RebindResult::Canceled)
+            } else if held.is_empty() && !capture.chord.is_empty() {
+                Some(RebindResult::Bound(mem::take(&mut capture.chord).into()))
+            } else if capture
+                .rebind
+                .timeout
+                .is_some_and(|timeout| now.saturating_sub(started) >= timeout)
+            {
+                Some(RebindResult::TimedOut)
+            } else {
+                capture.chord.extend(&held);
+                None
+            }
+        } else {
+            None
+        };
+        if let Some(result) = result {
+            let capture = rebinder.capture.take().unwrap();
+            if let RebindResult::Bound(binding) = &result {
+                let action = capture.rebind.action.clone();
+                match capture.rebind.apply {
+                    Apply::None => {}
+                    Apply::Append => {
+                        input_map.bind(action, binding.clone());
+                    }
+                    Apply::Replace(index) => {
+                        if input_map
+                            .replace_binding(action.clone(), index, binding.clone())
+                            .is_none()
+                        {
+                            input_map.bind(action, binding.clone());
+                        }
+                    }
+                }
+            }
+            events.send(RebindEvent {
+                action: capture.rebind.action,
+                result,
+            });
+        }
+    }
+}
+
+#[test]
+fn test_rebind() {
+    use bevy::{ecs::event::Events, input::InputPlugin};
+
+    use crate::{ActionPlugin, SimulateInput};
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_plugin(ActionPlugin::<String>::default());
+        app
+    }
+
+    fn start(app: &mut App, rebind: Rebind<String>) {
+        app.world.resource_mut::<Rebinder<String>>().start(rebind);
+    }
+
+    fn results(app: &App) -> Vec<RebindResult> {
+        let events = app.world.resource::<Events<RebindEvent<String>>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|v| v.result.clone())
+            .collect()
+    }
+
+    // A keyboard chord is captured once all of its keys are released
+    let mut app = new_app();
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("save", KeyCode::F5);
+    start(&mut app, Rebind::new("save").replace(0));
+    app.press_key(KeyCode::LControl).update();
+    app.press_key(KeyCode::S).update();
+    app.release_key(KeyCode::S).update();
+    assert!(results(&app).is_empty());
+    app.release_key(KeyCode::LControl).update();
+    let save = Binding::from(vec![KeyCode::LControl, KeyCode::S]);
+    assert_eq!(results(&app), vec![RebindResult::Bound(save.clone())]);
+    assert_eq!(
+        app.world.resource::<InputMap<String>>().bindings("save"),
+        std::slice::from_ref(&save)
+    );
+    // Replacing a binding that doesn't exist adds it instead
+    start(&mut app, Rebind::new("save").replace(1));
+    app.press_key(KeyCode::F6).update();
+    app.release_key(KeyCode::F6).update();
+    assert_eq!(
+        app.world.resource::<InputMap<String>>().bindings("save"),
+        [save, KeyCode::F6.into()]
+    );
+
+    // Inputs held when the capture starts are ignored until released
+    let mut app = new_app();
+    app.press_key(KeyCode::W).update();
+    start(&mut app, Rebind::new("jump"));
+    app.update();
+    app.press_key(KeyCode::Space).update();
+    app.release_key(KeyCode::Space).update();
+    assert_eq!(
+        results(&app),
+        vec![RebindResult::Bound(KeyCode::Space.into())]
+    );
+    assert!(app
+        .world
+        .resource::<InputMap<String>>()
+        .bindings("jump")
+        .is_empty());
+
+    // The cancel key ends the capture without being captured
+    let mut app = new_app();
+    start(&mut app, Rebind::new("jump"));
+    app.press_key(KeyCode::Space).update();
+    app.press_key(KeyCode::Escape).update();
+    assert_eq!(results(&app), vec![RebindResult::Canceled]);
+    assert!(app
+        .world
+        .resource::<Rebinder<String>>()
+        .capturing()
+        .is_none());
+
+    // Nothing pressed before the timeout
+    let mut app = new_app();
+    start(
+        &mut app,
+        Rebind::new("jump").with_timeout(Duration::from_millis(10)),
+    );
+    app.update();
+    std::thread::sleep(Duration::from_millis(20));
+    app.update();
+    assert_eq!(results(&app), vec![RebindResult::TimedOut]);
+
+    // Only input from the allowed devices is captured
+    let mut app = new_app();
+    start(
+        &mut app,
+        Rebind::new("fire").with_devices(RebindDevices {
+            keyboard: false,
+            ..default()
+        }),
+    );
+    app.send_gamepad_event(Gamepad(0), GamepadEventType::Connected)
+        .update();
+    app.press_key(KeyCode::LShift)
+        .press_mouse_button(MouseButton::Left)
+        .set_gamepad_button(Gamepad(0), GamepadButtonType::South, 1.)
+        .update();
+    app.release_key(KeyCode::LShift)
+        .release_mouse_button(MouseButton::Left)
+        .set_gamepad_button(Gamepad(0), GamepadButtonType::South, 0.)
+        .update();
+    let binding = Binding {
+        mouse_buttons: [MouseButton::Left].into_iter().collect(),
+        gamepad_buttons: [GamepadButtonType::South].into_iter().collect(),
+        ..default()
+    };
+    assert_eq!(results(&app), vec![RebindResult::Bound(binding)]);
+}
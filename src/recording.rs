@@ -0,0 +1,339 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use bevy::prelude::*;
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::InputMap;
+
+/// The actions triggered during one frame of a [`Recording`].
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(bound(deserialize = "T: Deserialize<'de> + Eq + Hash"))
+)]
+pub struct RecordedFrame<T> {
+    /// The active actions and their strengths.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashMap::is_empty")
+    )]
+    pub active: HashMap<T, f32>,
+    /// The actions that were just triggered, including those triggered again while active, eg. by
+    /// key repeat.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    pub just_active: HashSet<T>,
+}
+
+impl<T> Default for RecordedFrame<T> {
+    fn default() -> Self {
+        Self {
+            active: HashMap::new(),
+            just_active: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> PartialEq for RecordedFrame<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.active == other.active && self.just_active == other.just_active
+    }
+}
+
+/// The actions triggered by the [`InputMap`] resource frame by frame, captured by the
+/// [`ActionRecorder`] and replayed by the [`ActionPlayer`]. With the `serialize` feature,
+/// recordings can be saved to a file in any serde format, eg. to attach to a bug report or replay
+/// as a regression test.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(transparent, bound(deserialize = "T: Deserialize<'de> + Eq + Hash"))
+)]
+pub struct Recording<T> {
+    frames: Vec<RecordedFrame<T>>,
+}
+
+impl<T: Hash + Eq> PartialEq for Recording<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.frames == other.frames
+    }
+}
+
+impl<T> Default for Recording<T> {
+    fn default() -> Self {
+        Self { frames: Vec::new() }
+    }
+}
+
+impl<T> Recording<T> {
+    /// Returns the number of frames recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns whether no frames were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the frame at the given index.
+    pub fn frame(&self, index: usize) -> Option<&RecordedFrame<T>> {
+        self.frames.get(index)
+    }
+
+    /// Iterates over the recorded frames.
+    pub fn frames(&self) -> impl Iterator<Item = &RecordedFrame<T>> {
+        self.frames.iter()
+    }
+
+    /// Appends a frame to the recording.
+    pub fn push(&mut self, frame: RecordedFrame<T>) {
+        self.frames.push(frame);
+    }
+}
+
+/// A Bevy resource recording the actions triggered by the [`InputMap`] resource every frame
+/// between calls to [`ActionRecorder::start`] and [`ActionRecorder::stop`].
+pub struct ActionRecorder<T> {
+    recording: Option<Recording<T>>,
+}
+
+impl<T> Default for ActionRecorder<T> {
+    fn default() -> Self {
+        Self { recording: None }
+    }
+}
+
+impl<T> ActionRecorder<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Starts a new recording, discarding any in progress.
+    pub fn start(&mut self) {
+        self.recording = Some(default());
+    }
+
+    /// Stops recording, returning what was recorded since the call to [`ActionRecorder::start`].
+    pub fn stop(&mut self) -> Option<Recording<T>> {
+        self.recording.take()
+    }
+
+    /// Returns whether a recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// System that records the actions resolved this frame
+    pub(crate) fn record(mut recorder: ResMut<ActionRecorder<T>>, input_map: Res<InputMap<T>>)
+    where
+        T: 'static,
+    {
+        if let Some(recording) = &mut recorder.recording {
+            let state = input_map.state();
+            recording.push(RecordedFrame {
+                active: state.active.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+                just_active: state.just_active.keys().cloned().collect(),
+            });
+        }
+    }
+}
+
+/// Whether an [`ActionPlayer`] replaces live input or adds to it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PlaybackMode {
+    /// Live input is ignored during playback, so that the [`InputMap`] resource triggers exactly
+    /// the recorded actions.
+    Exclusive,
+    /// Actions triggered by live input are kept alongside the recorded ones.
+    Merged,
+}
+
+/// A Bevy resource replaying a [`Recording`] into the [`InputMap`] resource, one frame at a time
+/// from the frame after the call to [`ActionPlayer::play`]. Actions become active and inactive as
+/// recorded, and are just active on the same frames.
+pub struct ActionPlayer<T> {
+    recording: Option<Recording<T>>,
+    frame: usize,
+    mode: PlaybackMode,
+}
+
+impl<T> Default for ActionPlayer<T> {
+    fn default() -> Self {
+        Self {
+            recording: None,
+            frame: 0,
+            mode: PlaybackMode::Exclusive,
+        }
+    }
+}
+
+impl<T> ActionPlayer<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Starts replaying a recording from its first frame, stopping any playback in progress.
+    pub fn play(&mut self, recording: Recording<T>, mode: PlaybackMode) {
+        self.recording = Some(recording);
+        self.frame = 0;
+        self.mode = mode;
+    }
+
+    /// Stops playback, returning the recording being played. Playback also stops on its own after
+    /// the last frame.
+    pub fn stop(&mut self) -> Option<Recording<T>> {
+        self.recording.take()
+    }
+
+    /// Continues playback from the given frame. Actions that are active in both the current and
+    /// the given frame stay active, and only those just active in the given frame are just active.
+    pub fn seek(&mut self, frame: usize) {
+        self.frame = frame;
+    }
+
+    /// Returns the index of the next frame to be replayed.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Returns whether a recording is being played.
+    pub fn is_playing(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Returns how playback interacts with live input.
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    /// System that feeds the next recorded frame to the [`InputMap`] resource
+    pub(crate) fn replay(
+        mut player: ResMut<ActionPlayer<T>>,
+        mut input_map: ResMut<InputMap<T>>,
+        mut live_input: Local<Option<bool>>,
+    ) where
+        T: 'static,
+    {
+        let player = &mut *player;
+        let frame = player
+            .recording
+            .as_ref()
+            .and_then(|v| v.frame(player.frame))
+            .cloned();
+        match frame {
+            Some(frame) => {
+                // Remember whether live input was enabled, to restore it once playback ends
+                let enabled = *live_input.get_or_insert(input_map.live_input);
+                input_map.live_input = enabled && player.mode == PlaybackMode::Merged;
+                input_map.replayed = Some(frame);
+                player.frame += 1;
+            }
+            None => {
+                player.recording = None;
+                // Restore live input once, so as not to override it otherwise
+                if let Some(live_input) = live_input.take() {
+                    input_map.live_input = live_input;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_recording() {
+    use bevy::input::InputPlugin;
+
+    use crate::{ActionPlugin, SimulateInput};
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin::<String>::default());
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("jump", KeyCode::Space)
+        .bind("crouch", KeyCode::C)
+        .bind("slide", vec![KeyCode::C, KeyCode::LShift]);
+    app.world.resource_mut::<ActionRecorder<String>>().start();
+    app.press_key(KeyCode::Space).update();
+    app.release_key(KeyCode::Space)
+        .press_key(KeyCode::C)
+        .update();
+    app.press_key(KeyCode::LShift).update();
+    // Crouch is active again without being just active
+    app.release_key(KeyCode::LShift).update();
+    app.release_key(KeyCode::C).update();
+    let recording = app
+        .world
+        .resource_mut::<ActionRecorder<String>>()
+        .stop()
+        .unwrap();
+    assert_eq!(recording.len(), 5);
+
+    #[cfg(feature = "serialize")]
+    let recording = {
+        let saved = ron::to_string(&recording).unwrap();
+        ron::from_str::<Recording<String>>(&saved).unwrap()
+    };
+
+    // Replayed while holding a key that would otherwise trigger jump
+    app.press_key(KeyCode::Space).update();
+    app.world
+        .resource_mut::<ActionPlayer<String>>()
+        .play(recording.clone(), PlaybackMode::Exclusive);
+    let mut replayed = Recording::default();
+    for _ in 0..recording.len() {
+        app.update();
+        let state = app.world.resource::<InputMap<String>>().state();
+        replayed.push(RecordedFrame {
+            active: state.active.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            just_active: state.just_active.keys().cloned().collect(),
+        });
+    }
+    assert_eq!(replayed, recording);
+    assert!(!app.world.resource::<InputMap<String>>().active("jump"));
+    app.update();
+    assert!(!app.world.resource::<ActionPlayer<String>>().is_playing());
+    assert!(app.world.resource::<InputMap<String>>().active("jump"));
+
+    // Seeking keeps actions active in both frames, which are only just active if they were then
+    app.world
+        .resource_mut::<ActionPlayer<String>>()
+        .play(recording.clone(), PlaybackMode::Exclusive);
+    app.update();
+    app.update();
+    let map = |app: &App| app.world.resource::<InputMap<String>>().state().clone();
+    assert!(map(&app).just_active("crouch"));
+    app.world.resource_mut::<ActionPlayer<String>>().seek(3);
+    app.update();
+    assert!(map(&app).active("crouch") && !map(&app).just_active("crouch"));
+    app.world.resource_mut::<ActionPlayer<String>>().seek(1);
+    app.update();
+    assert!(map(&app).just_active("crouch") && !map(&app).active("jump"));
+    app.world.resource_mut::<ActionPlayer<String>>().seek(0);
+    app.update();
+    assert!(map(&app).just_active("jump") && map(&app).just_inactive("crouch"));
+    app.world.resource_mut::<ActionPlayer<String>>().stop();
+    app.update();
+
+    // Live input stays disabled after playback if it was before
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .set_live_input(false);
+    app.world
+        .resource_mut::<ActionPlayer<String>>()
+        .play(recording.clone(), PlaybackMode::Merged);
+    for _ in 0..=recording.len() {
+        app.update();
+    }
+    assert!(!app.world.resource::<ActionPlayer<String>>().is_playing());
+    assert!(!app.world.resource::<InputMap<String>>().live_input());
+}
@@ -0,0 +1,137 @@
+use std::hash::Hash;
+
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
+
+use crate::{ActionState, InputMap};
+
+fn should_run(condition: bool) -> ShouldRun {
+    if condition {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Run criteria that runs a system on every frame during which the given action is triggered.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_input_actionmap::*;
+/// # fn fire() {}
+/// App::new().add_system(fire.with_run_criteria(action_active("FIRE".to_string())));
+/// ```
+pub fn action_active<T>(action: T) -> impl FnMut(Res<InputMap<T>>) -> ShouldRun
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    move |input_map: Res<InputMap<T>>| should_run(input_map.active(action.clone()))
+}
+
+/// Run criteria that runs a system on every frame during which the given action is not triggered.
+pub fn action_inactive<T>(action: T) -> impl FnMut(Res<InputMap<T>>) -> ShouldRun
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    move |input_map: Res<InputMap<T>>| should_run(!input_map.active(action.clone()))
+}
+
+/// Run criteria that runs a system on the frame the given action has just been triggered.
+pub fn action_just_active<T>(action: T) -> impl FnMut(Res<InputMap<T>>) -> ShouldRun
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    move |input_map: Res<InputMap<T>>| should_run(input_map.just_active(action.clone()))
+}
+
+/// Run criteria that runs a system on the frame the given action has just stopped being
+/// triggered.
+pub fn action_just_inactive<T>(action: T) -> impl FnMut(Res<InputMap<T>>) -> ShouldRun
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    move |input_map: Res<InputMap<T>>| should_run(input_map.just_inactive(action.clone()))
+}
+
+/// Run criteria meant to be chained after a fixed timestep, such as
+/// [`bevy::core::FixedTimestep`], which starts a new fixed tick of every [`InputMap`] and
+/// [`ActionState`] each time the fixed step runs. Systems in the step can then read
+/// [`InputMap::fixed_just_active`] and [`InputMap::fixed_just_inactive`] to see each transition
+/// exactly once, however many times the step runs per frame.
+///
+/// ```no_run
+/// # use bevy::{core::FixedTimestep, prelude::*};
+/// # use bevy_input_actionmap::*;
+/// # fn physics() {}
+/// App::new().add_system_set(
+///     SystemSet::new()
+///         .with_run_criteria(FixedTimestep::step(1. / 60.).chain(tick_fixed_actions::<String>))
+///         .with_system(physics),
+/// );
+/// ```
+pub fn tick_fixed_actions<T>(
+    In(should_run): In<ShouldRun>,
+    mut input_map: ResMut<InputMap<T>>,
+    mut input_maps: Query<&mut InputMap<T>>,
+    mut states: Query<&mut ActionState<T>>,
+) -> ShouldRun
+where
+    T: 'static + Hash + Eq + Clone + Send + Sync,
+{
+    if matches!(should_run, ShouldRun::Yes | ShouldRun::YesAndCheckAgain) {
+        input_map.tick_fixed();
+        for mut input_map in input_maps.iter_mut() {
+            input_map.tick_fixed();
+        }
+        for mut state in states.iter_mut() {
+            state.tick_fixed();
+        }
+    }
+    should_run
+}
+
+#[test]
+fn test_run_criteria() {
+    use bevy::input::InputPlugin;
+
+    use crate::{ActionPlugin, SimulateInput};
+
+    #[derive(Default)]
+    struct Runs {
+        jumped: Vec<bool>,
+        idle: Vec<bool>,
+    }
+
+    fn jumped(mut runs: ResMut<Runs>) {
+        *runs.jumped.last_mut().unwrap() = true;
+    }
+
+    fn idle(mut runs: ResMut<Runs>) {
+        *runs.idle.last_mut().unwrap() = true;
+    }
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin::<String>::default())
+        .init_resource::<Runs>()
+        .add_system(jumped.with_run_criteria(action_just_active("jump".to_string())))
+        .add_system(idle.with_run_criteria(action_inactive("jump".to_string())));
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("jump", KeyCode::Space);
+    let frame = |app: &mut App| {
+        let mut runs = app.world.resource_mut::<Runs>();
+        runs.jumped.push(false);
+        runs.idle.push(false);
+        app.update();
+    };
+    frame(&mut app);
+    app.press_key(KeyCode::Space);
+    frame(&mut app);
+    frame(&mut app);
+    app.release_key(KeyCode::Space);
+    frame(&mut app);
+    let runs = app.world.resource::<Runs>();
+    assert_eq!(runs.jumped, vec![false, true, false, false]);
+    assert_eq!(runs.idle, vec![true, false, false, true]);
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::{keyboard::KeyboardInput, ElementState},
+    prelude::*,
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::Binding;
+
+/// A physical key, identified by the platform's scan code from [`KeyboardInput`] events.
+/// Bindings using scan codes trigger from the same position on the keyboard whatever the
+/// player's layout, eg. for WASD movement on AZERTY keyboards.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(transparent)
+)]
+pub struct ScanCode(pub u32);
+
+/// The scan code of simulated key events for keys without a known scan code, which isn't tracked.
+pub(crate) const NO_SCAN_CODE: u32 = u32::MAX;
+
+impl ScanCode {
+    /// Returns the scan code of the key labeled with the given key on a US QWERTY keyboard, for
+    /// the letter, digit and punctuation keys of the main block.
+    pub fn from_qwerty(key: KeyCode) -> Option<Self> {
+        QWERTY.iter().find(|v| v.0 == key).map(|v| ScanCode(v.1))
+    }
+}
+
+impl From<ScanCode> for Binding {
+    fn from(scan_code: ScanCode) -> Self {
+        vec![scan_code].into()
+    }
+}
+
+impl From<Vec<ScanCode>> for Binding {
+    fn from(scan_codes: Vec<ScanCode>) -> Self {
+        Self {
+            scan_codes: scan_codes.into_iter().collect(),
+            ..default()
+        }
+    }
+}
+
+impl Binding {
+    /// Binds the physical position of a key on a US QWERTY keyboard, so that eg.
+    /// `Binding::physical(KeyCode::W)` is bound to Z on AZERTY keyboards. Falls back to the key
+    /// itself for keys outside of the main block.
+    pub fn physical(key: KeyCode) -> Self {
+        ScanCode::from_qwerty(key)
+            .map(Binding::from)
+            .unwrap_or_else(|| key.into())
+    }
+}
+
+/// A Bevy resource mapping scan codes to the keys they produce on the player's layout, eg. to
+/// label bindings made with [`Binding::physical`]. Keys are learned from the keyboard events sent
+/// as they are pressed, as the platform can't be asked for the layout.
+///
+/// Until the player has pressed a key, its scan code has no label, so UIs should fall back to
+/// something else, eg. the QWERTY key the binding was made from, when [`KeyboardLayout::key_code`]
+/// returns None.
+#[derive(Debug, Default)]
+pub struct KeyboardLayout {
+    learned: HashMap<ScanCode, KeyCode>,
+}
+
+impl KeyboardLayout {
+    /// Returns the key produced by the given scan code, or None if that key hasn't been pressed
+    /// yet. Labels for unpressed keys can't be guessed, as eg. the key in the position of W on a
+    /// QWERTY keyboard produces Z on AZERTY layouts.
+    pub fn key_code(&self, scan_code: ScanCode) -> Option<KeyCode> {
+        self.learned.get(&scan_code).copied()
+    }
+
+    /// System that tracks pressed scan codes and the keys they produce
+    pub(crate) fn scan_code_input(
+        mut events: EventReader<KeyboardInput>,
+        mut input: ResMut<Input<ScanCode>>,
+        mut layout: ResMut<KeyboardLayout>,
+    ) {
+        input.clear();
+        for event in events.iter().filter(|v| v.scan_code != NO_SCAN_CODE) {
+            let scan_code = ScanCode(event.scan_code);
+            if let Some(key_code) = event.key_code {
+                layout.learned.insert(scan_code, key_code);
+            }
+            match event.state {
+                ElementState::Pressed => input.press(scan_code),
+                ElementState::Released => input.release(scan_code),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+const QWERTY: [(KeyCode, u32); 48] = [
+    (KeyCode::Key1, 0x02),
+    (KeyCode::Key2, 0x03),
+    (KeyCode::Key3, 0x04),
+    (KeyCode::Key4, 0x05),
+    (KeyCode::Key5, 0x06),
+    (KeyCode::Key6, 0x07),
+    (KeyCode::Key7, 0x08),
+    (KeyCode::Key8, 0x09),
+    (KeyCode::Key9, 0x0a),
+    (KeyCode::Key0, 0x0b),
+    (KeyCode::Minus, 0x0c),
+    (KeyCode::Equals, 0x0d),
+    (KeyCode::Q, 0x10),
+    (KeyCode::W, 0x11),
+    (KeyCode::E, 0x12),
+    (KeyCode::R, 0x13),
+    (KeyCode::T, 0x14),
+    (KeyCode::Y, 0x15),
+    (KeyCode::U, 0x16),
+    (KeyCode::I, 0x17),
+    (KeyCode::O, 0x18),
+    (KeyCode::P, 0x19),
+    (KeyCode::LBracket, 0x1a),
+    (KeyCode::RBracket, 0x1b),
+    (KeyCode::A, 0x1e),
+    (KeyCode::S, 0x1f),
+    (KeyCode::D, 0x20),
+    (KeyCode::F, 0x21),
+    (KeyCode::G, 0x22),
+    (KeyCode::H, 0x23),
+    (KeyCode::J, 0x24),
+    (KeyCode::K, 0x25),
+    (KeyCode::L, 0x26),
+    (KeyCode::Semicolon, 0x27),
+    (KeyCode::Apostrophe, 0x28),
+    (KeyCode::Grave, 0x29),
+    (KeyCode::Backslash, 0x2b),
+    (KeyCode::Z, 0x2c),
+    (KeyCode::X, 0x2d),
+    (KeyCode::C, 0x2e),
+    (KeyCode::V, 0x2f),
+    (KeyCode::B, 0x30),
+    (KeyCode::N, 0x31),
+    (KeyCode::M, 0x32),
+    (KeyCode::Comma, 0x33),
+    (KeyCode::Period, 0x34),
+    (KeyCode::Slash, 0x35),
+    (KeyCode::Space, 0x39),
+];
+
+#[cfg(target_os = "macos")]
+const QWERTY: [(KeyCode, u32); 48] = [
+    (KeyCode::A, 0x00),
+    (KeyCode::S, 0x01),
+    (KeyCode::D, 0x02),
+    (KeyCode::F, 0x03),
+    (KeyCode::H, 0x04),
+    (KeyCode::G, 0x05),
+    (KeyCode::Z, 0x06),
+    (KeyCode::X, 0x07),
+    (KeyCode::C, 0x08),
+    (KeyCode::V, 0x09),
+    (KeyCode::B, 0x0b),
+    (KeyCode::Q, 0x0c),
+    (KeyCode::W, 0x0d),
+    (KeyCode::E, 0x0e),
+    (KeyCode::R, 0x0f),
+    (KeyCode::Y, 0x10),
+    (KeyCode::T, 0x11),
+    (KeyCode::Key1, 0x12),
+    (KeyCode::Key2, 0x13),
+    (KeyCode::Key3, 0x14),
+    (KeyCode::Key4, 0x15),
+    (KeyCode::Key6, 0x16),
+    (KeyCode::Key5, 0x17),
+    (KeyCode::Equals, 0x18),
+    (KeyCode::Key9, 0x19),
+    (KeyCode::Key7, 0x1a),
+    (KeyCode::Minus, 0x1b),
+    (KeyCode::Key8, 0x1c),
+    (KeyCode::Key0, 0x1d),
+    (KeyCode::RBracket, 0x1e),
+    (KeyCode::O, 0x1f),
+    (KeyCode::U, 0x20),
+    (KeyCode::LBracket, 0x21),
+    (KeyCode::I, 0x22),
+    (KeyCode::P, 0x23),
+    (KeyCode::L, 0x25),
+    (KeyCode::J, 0x26),
+    (KeyCode::Apostrophe, 0x27),
+    (KeyCode::K, 0x28),
+    (KeyCode::Semicolon, 0x29),
+    (KeyCode::Backslash, 0x2a),
+    (KeyCode::Comma, 0x2b),
+    (KeyCode::Slash, 0x2c),
+    (KeyCode::N, 0x2d),
+    (KeyCode::M, 0x2e),
+    (KeyCode::Period, 0x2f),
+    (KeyCode::Space, 0x31),
+    (KeyCode::Grave, 0x32),
+];
+
+#[test]
+fn test_scan_codes() {
+    use std::time::Duration;
+
+    use bevy::ecs::event::Events;
+
+    use crate::InputMap;
+
+    let mut map = InputMap::<String>::default();
+    map.bind("up", Binding::physical(KeyCode::W))
+        .bind("jump", KeyCode::Space);
+    let mut scan_codes = Input::<ScanCode>::default();
+    // Z on AZERTY keyboards
+    scan_codes.press(ScanCode::from_qwerty(KeyCode::W).unwrap());
+    let mut keys = Input::<KeyCode>::default();
+    keys.press(KeyCode::Z);
+    map.update_keys(&keys, &scan_codes);
+    map.update_active(&keys, &scan_codes, Duration::ZERO);
+    assert!(map.just_active("up"));
+    assert_eq!(
+        Binding::physical(KeyCode::Escape),
+        Binding::from(KeyCode::Escape)
+    );
+
+    let mut world = World::default();
+    world.init_resource::<Events<KeyboardInput>>();
+    world.init_resource::<Input<ScanCode>>();
+    world.init_resource::<KeyboardLayout>();
+    let mut system = IntoSystem::into_system(KeyboardLayout::scan_code_input);
+    system.initialize(&mut world);
+    let w = ScanCode::from_qwerty(KeyCode::W).unwrap();
+    assert_eq!(world.resource::<KeyboardLayout>().key_code(w), None);
+    world
+        .resource_mut::<Events<KeyboardInput>>()
+        .send(KeyboardInput {
+            scan_code: w.0,
+            key_code: Some(KeyCode::Z),
+            state: ElementState::Pressed,
+        });
+    system.run((), &mut world);
+    assert_eq!(
+        world.resource::<KeyboardLayout>().key_code(w),
+        Some(KeyCode::Z)
+    );
+    assert!(world.resource::<Input<ScanCode>>().just_pressed(w));
+}
@@ -72,6 +72,23 @@ where
         Ok(map)
     }
 }
+impl<T> InputMap<T>
+where
+    T: Eq + Hash + Clone + Send + Sync,
+{
+    /// Deserializes bindings and applies them as with [`InputMap::apply_bindings`], keeping
+    /// connected gamepads and the state of actions whose bindings didn't change.
+    pub fn load_bindings<'de, D>(&mut self, deserializer: D) -> Result<&mut Self, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let other = InputMap::<T>::deserialize(deserializer)?;
+        Ok(self.apply_bindings(other))
+    }
+}
+
+#[cfg(test)]
 #[derive(Hash, PartialEq, Eq, Serialize, Deserialize, Debug, Copy, Clone)]
 enum TestAction {
     Select,
@@ -99,9 +116,44 @@ fn test_serialize_to_string() {
     map.bind(
         TestAction::AwesomeSuperSelect,
         bevy::prelude::GamepadButtonType::North,
-    );
+    )
+    .set_priority(TestAction::AwesomeSuperSelect, 1);
     let serialized = ron::to_string(&map).expect("Failed serialization");
     let deserialized: InputMap<TestAction> =
         ron::from_str(&serialized).expect("Failed deserialization");
     assert_eq!(map.actions, deserialized.actions);
 }
+
+#[test]
+fn test_deserialize_string_bindings() {
+    let saved = r#"{ Select: (bindings: ["LControl+Space", "Gamepad:North"]) }"#;
+    let deserialized: InputMap<TestAction> = ron::from_str(saved).expect("Failed deserialization");
+    assert_eq!(
+        deserialized.bindings(TestAction::Select),
+        vec![
+            vec![
+                bevy::prelude::KeyCode::LControl,
+                bevy::prelude::KeyCode::Space
+            ]
+            .into(),
+            bevy::prelude::GamepadButtonType::North.into()
+        ]
+    );
+}
+
+#[test]
+fn test_deserialize_struct_bindings() {
+    let saved = "{
+        Select: (bindings: [(
+            keys: [Space],
+            gamepad_buttons: [],
+            gamepad_axis_directions: [],
+            deadzone: 0,
+        )]),
+    }";
+    let deserialized: InputMap<TestAction> = ron::from_str(saved).expect("Failed deserialization");
+    assert_eq!(
+        deserialized.bindings(TestAction::Select),
+        vec![bevy::prelude::KeyCode::Space.into()]
+    );
+}
@@ -0,0 +1,250 @@
+use std::hash::Hash;
+
+use bevy::{
+    ecs::event::Events,
+    input::{
+        gamepad::GamepadEventRaw, keyboard::KeyboardInput, mouse::MouseButtonInput, ElementState,
+    },
+    prelude::*,
+    window::WindowId,
+};
+
+use crate::{scan_code::NO_SCAN_CODE, Binding, InputMap, ScanCode};
+
+impl<T> InputMap<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Holds an action as though one of its bindings were held, until it is released with
+    /// [`InputMap::release`]. Takes effect when conflicts are next resolved, like real input, and
+    /// never conflicts with other actions. Useful to drive the actions of AI-controlled or
+    /// scripted characters, or to test gameplay systems.
+    pub fn press<K: Into<T>>(&mut self, action: K) -> &mut Self {
+        self.set_strength(action, 1.)
+    }
+
+    /// Releases an action held with [`InputMap::press`] or [`InputMap::set_strength`]. The action
+    /// stays active if its bindings are held.
+    pub fn release<K: Into<T>>(&mut self, action: K) -> &mut Self {
+        self.simulated.remove(&action.into());
+        self
+    }
+
+    /// Holds an action with the given strength as with [`InputMap::press`], eg. to pull a
+    /// simulated stick halfway. A strength of 0 releases the action.
+    pub fn set_strength<K: Into<T>>(&mut self, action: K, strength: f32) -> &mut Self {
+        if strength == 0. {
+            self.release(action)
+        } else {
+            self.simulated.insert(action.into(), strength);
+            self
+        }
+    }
+
+    /// Sets whether actions are triggered by input from devices, as opposed to only those held
+    /// with [`InputMap::press`], eg. to keep the player from interfering with a scripted sequence.
+    pub fn set_live_input(&mut self, live_input: bool) -> &mut Self {
+        self.live_input = live_input;
+        self
+    }
+
+    /// Returns whether actions are triggered by input from devices.
+    pub fn live_input(&self) -> bool {
+        self.live_input
+    }
+
+    /// Maps the actions held with [`InputMap::press`] and [`InputMap::set_strength`]
+    pub(crate) fn update_simulated(&mut self) {
+        for (k, strength) in &self.simulated {
+            self.raw_active
+                .push((k.clone(), Binding::default(), *strength));
+        }
+    }
+}
+
+/// Injects raw input as though it came from real devices, so that it flows through Bevy's input
+/// systems and those of the [`ActionPlugin`] just like real input. Events are read the next time
+/// the schedule runs, so that pressing a key then calling `App::update` triggers its actions.
+///
+/// Keyboard, mouse and gamepad input requires Bevy's `InputPlugin`, and typed characters the
+/// `WindowPlugin`. Gamepad input goes through the `GamepadSettings` as that of a real gamepad.
+///
+/// [`ActionPlugin`]: crate::ActionPlugin
+pub trait SimulateInput {
+    /// Presses a key. Keys of the main block also press their [`ScanCode`] on a US QWERTY layout.
+    fn press_key(&mut self, key: KeyCode) -> &mut Self;
+
+    /// Releases a key pressed with [`SimulateInput::press_key`].
+    fn release_key(&mut self, key: KeyCode) -> &mut Self;
+
+    /// Presses a mouse button.
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+
+    /// Releases a mouse button.
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+
+    /// Types a character, as reported by the window when a key is typed.
+    fn type_character(&mut self, character: char) -> &mut Self;
+
+    /// Sends a gamepad event, eg. to connect a gamepad or move one of its axes.
+    fn send_gamepad_event(&mut self, gamepad: Gamepad, event: GamepadEventType) -> &mut Self;
+
+    /// Sets how far a gamepad button is pressed, between 0 and 1.
+    fn set_gamepad_button(
+        &mut self,
+        gamepad: Gamepad,
+        button: GamepadButtonType,
+        value: f32,
+    ) -> &mut Self {
+        self.send_gamepad_event(gamepad, GamepadEventType::ButtonChanged(button, value))
+    }
+
+    /// Moves a gamepad axis to the given position, between -1 and 1.
+    fn set_gamepad_axis(
+        &mut self,
+        gamepad: Gamepad,
+        axis: GamepadAxisType,
+        value: f32,
+    ) -> &mut Self {
+        self.send_gamepad_event(gamepad, GamepadEventType::AxisChanged(axis, value))
+    }
+}
+
+impl SimulateInput for World {
+    fn press_key(&mut self, key: KeyCode) -> &mut Self {
+        send_key(self, key, ElementState::Pressed);
+        self
+    }
+
+    fn release_key(&mut self, key: KeyCode) -> &mut Self {
+        send_key(self, key, ElementState::Released);
+        self
+    }
+
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.resource_mut::<Events<MouseButtonInput>>()
+            .send(MouseButtonInput {
+                button,
+                state: ElementState::Pressed,
+            });
+        self
+    }
+
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.resource_mut::<Events<MouseButtonInput>>()
+            .send(MouseButtonInput {
+                button,
+                state: ElementState::Released,
+            });
+        self
+    }
+
+    fn type_character(&mut self, character: char) -> &mut Self {
+        self.resource_mut::<Events<ReceivedCharacter>>()
+            .send(ReceivedCharacter {
+                id: WindowId::primary(),
+                char: character,
+            });
+        self
+    }
+
+    fn send_gamepad_event(&mut self, gamepad: Gamepad, event: GamepadEventType) -> &mut Self {
+        self.resource_mut::<Events<GamepadEventRaw>>()
+            .send(GamepadEventRaw(gamepad, event));
+        self
+    }
+}
+
+impl SimulateInput for App {
+    fn press_key(&mut self, key: KeyCode) -> &mut Self {
+        self.world.press_key(key);
+        self
+    }
+
+    fn release_key(&mut self, key: KeyCode) -> &mut Self {
+        self.world.release_key(key);
+        self
+    }
+
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.world.press_mouse_button(button);
+        self
+    }
+
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.world.release_mouse_button(button);
+        self
+    }
+
+    fn type_character(&mut self, character: char) -> &mut Self {
+        self.world.type_character(character);
+        self
+    }
+
+    fn send_gamepad_event(&mut self, gamepad: Gamepad, event: GamepadEventType) -> &mut Self {
+        self.world.send_gamepad_event(gamepad, event);
+        self
+    }
+}
+
+fn send_key(world: &mut World, key: KeyCode, state: ElementState) {
+    let scan_code = ScanCode::from_qwerty(key).map_or(NO_SCAN_CODE, |v| v.0);
+    world
+        .resource_mut::<Events<KeyboardInput>>()
+        .send(KeyboardInput {
+            scan_code,
+            key_code: Some(key),
+            state,
+        });
+}
+
+#[test]
+fn test_simulated_input() {
+    use std::time::Duration;
+
+    use bevy::input::InputPlugin;
+
+    use crate::ActionPlugin;
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .press("jump")
+        .set_strength("move", 0.5);
+    map.update_active(&default(), &default(), Duration::ZERO);
+    assert!(map.just_active("jump"));
+    assert_eq!(map.strength("move"), 0.5);
+    map.state.clear_just_active_inactive();
+    map.release("jump").set_strength("move", 0.);
+    map.update_active(&default(), &default(), Duration::ZERO);
+    assert!(map.just_inactive("jump"));
+    assert!(map.just_inactive("move"));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin::<String>::default());
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("jump", KeyCode::Space)
+        .bind("jump", GamepadButtonType::South);
+    app.press_key(KeyCode::Space).update();
+    assert!(app.world.resource::<InputMap<String>>().just_active("jump"));
+    app.release_key(KeyCode::Space).update();
+    assert!(app
+        .world
+        .resource::<InputMap<String>>()
+        .just_inactive("jump"));
+    app.send_gamepad_event(Gamepad(0), GamepadEventType::Connected)
+        .set_gamepad_button(Gamepad(0), GamepadButtonType::South, 1.)
+        .update();
+    assert!(app.world.resource::<InputMap<String>>().just_active("jump"));
+
+    // Keys without a scan code don't press any
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .bind("up", KeyCode::Up)
+        .bind("scan", ScanCode(0));
+    app.press_key(KeyCode::Up).update();
+    assert!(app.world.resource::<InputMap<String>>().just_active("up"));
+    assert!(!app.world.resource::<InputMap<String>>().active("scan"));
+}
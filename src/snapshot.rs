@@ -0,0 +1,339 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{ActionState, InputMap};
+
+/// The triggered actions of an [`ActionState`] or [`InputMap`] at one frame, eg. to roll back to
+/// with rollback netcode, or to send to other players. With the `serialize` feature, snapshots
+/// can be sent in any serde format, and [`SnapshotDiff`]s between consecutive frames are smaller
+/// still.
+///
+/// Transitions accumulated for systems on a fixed timestep aren't part of the snapshot.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "T: Serialize",
+        deserialize = "T: Deserialize<'de> + Eq + Hash"
+    ))
+)]
+pub struct ActionSnapshot<T>
+where
+    T: Hash + Eq,
+{
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashMap::is_empty")
+    )]
+    active: HashMap<T, f32>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    just_active: HashSet<T>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    just_inactive: HashSet<T>,
+}
+
+impl<T> Default for ActionSnapshot<T>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            active: HashMap::new(),
+            just_active: HashSet::new(),
+            just_inactive: HashSet::new(),
+        }
+    }
+}
+
+/// The changes between two consecutive [`ActionSnapshot`]s, as computed by
+/// [`ActionSnapshot::diff`]. Frames in which no action changes are empty.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "T: Serialize",
+        deserialize = "T: Deserialize<'de> + Eq + Hash"
+    ))
+)]
+pub struct SnapshotDiff<T>
+where
+    T: Hash + Eq,
+{
+    /// Actions that became active or changed strength
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashMap::is_empty")
+    )]
+    changed: HashMap<T, f32>,
+    /// Actions that are no longer active
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    released: HashSet<T>,
+    /// Actions just triggered in the newer snapshot
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    just_active: HashSet<T>,
+    /// Actions just released in the newer snapshot
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    just_inactive: HashSet<T>,
+}
+
+impl<T> Default for SnapshotDiff<T>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            changed: HashMap::new(),
+            released: HashSet::new(),
+            just_active: HashSet::new(),
+            just_inactive: HashSet::new(),
+        }
+    }
+}
+
+impl<T> SnapshotDiff<T>
+where
+    T: Hash + Eq,
+{
+    /// Returns whether no action changed between the two snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+            && self.released.is_empty()
+            && self.just_active.is_empty()
+            && self.just_inactive.is_empty()
+    }
+}
+
+impl<T> ActionSnapshot<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Captures the triggered actions of the given state.
+    pub fn capture(state: &ActionState<T>) -> Self {
+        Self {
+            active: state.active.clone(),
+            just_active: state.just_active.keys().cloned().collect(),
+            just_inactive: state.just_inactive.clone(),
+        }
+    }
+
+    /// Replaces the triggered actions of the given state with those of this snapshot.
+    pub fn restore(&self, state: &mut ActionState<T>) {
+        state.active = self.active.clone();
+        state.just_active = self
+            .just_active
+            .iter()
+            .map(|k| (k.clone(), self.active.get(k).copied().unwrap_or(1.)))
+            .collect();
+        state.just_inactive = self.just_inactive.clone();
+    }
+
+    /// Returns whether a given action was triggered.
+    pub fn active<K: Into<T>>(&self, key: K) -> bool {
+        self.active.contains_key(&key.into())
+    }
+
+    /// Returns the changes from the previous snapshot to this one.
+    pub fn diff(&self, previous: &ActionSnapshot<T>) -> SnapshotDiff<T> {
+        SnapshotDiff {
+            changed: self
+                .active
+                .iter()
+                .filter(|(k, v)| previous.active.get(k) != Some(v))
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            released: previous
+                .active
+                .keys()
+                .filter(|k| !self.active.contains_key(k))
+                .cloned()
+                .collect(),
+            just_active: self.just_active.clone(),
+            just_inactive: self.just_inactive.clone(),
+        }
+    }
+
+    /// Applies the changes to the previous snapshot computed by [`ActionSnapshot::diff`], turning
+    /// this snapshot into the next one.
+    pub fn apply(&mut self, diff: &SnapshotDiff<T>) {
+        for k in &diff.released {
+            self.active.remove(k);
+        }
+        self.active
+            .extend(diff.changed.iter().map(|(k, v)| (k.clone(), *v)));
+        self.just_active = diff.just_active.clone();
+        self.just_inactive = diff.just_inactive.clone();
+    }
+}
+
+impl<T> InputMap<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    /// Captures the triggered actions, as with [`ActionSnapshot::capture`].
+    pub fn snapshot(&self) -> ActionSnapshot<T> {
+        ActionSnapshot::capture(&self.state)
+    }
+
+    /// Replaces the triggered actions with those of a snapshot on the next update, as though they
+    /// were triggered by input. Events, transitions for fixed timestep systems and the
+    /// [`ActionState`] component are updated from the snapshot, while its just triggered and just
+    /// released actions are kept as captured. To drive the actions of a remote player from network
+    /// data, disable its [`InputMap::set_live_input`] and restore each frame's snapshot before
+    /// [`ActionSystem::ResolveConflicts`].
+    ///
+    /// [`ActionSystem::ResolveConflicts`]: crate::ActionSystem::ResolveConflicts
+    pub fn restore(&mut self, snapshot: &ActionSnapshot<T>) -> &mut Self {
+        let mut state = ActionState::default();
+        snapshot.restore(&mut state);
+        self.restored = Some(state);
+        self
+    }
+}
+
+#[test]
+fn test_snapshots() {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space)
+        .bind("run", KeyCode::LShift);
+    let mut input = Input::<KeyCode>::default();
+    let mut snapshots = vec![map.snapshot()];
+    for key in [KeyCode::Space, KeyCode::LShift] {
+        map.state.clear_just_active_inactive();
+        input.clear();
+        input.press(key);
+        map.update_keys(&input, &default());
+        map.update_active(&input, &default(), Duration::ZERO);
+        snapshots.push(map.snapshot());
+    }
+    assert!(snapshots[2].active("jump") && snapshots[2].active("run"));
+
+    // Rebuild the snapshots from the diffs between them
+    let mut remote = snapshots[0].clone();
+    for pair in snapshots.windows(2) {
+        let diff = pair[1].diff(&pair[0]);
+        #[cfg(feature = "serialize")]
+        let diff = ron::from_str(&ron::to_string(&diff).unwrap()).unwrap();
+        remote.apply(&diff);
+        assert_eq!(remote, pair[1]);
+    }
+
+    // Roll back, then continue from the restored state
+    map.state.clear_just_active_inactive();
+    map.restore(&snapshots[1]);
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.just_active("jump") && !map.active("run"));
+    map.state.clear_just_active_inactive();
+    map.update_keys(&input, &default());
+    map.update_active(&input, &default(), Duration::ZERO);
+    assert!(map.active("run") && map.just_active("run"));
+    assert!(!map.just_active("jump"));
+}
+
+#[test]
+fn test_restored_events() {
+    use std::time::Duration;
+
+    use bevy::{ecs::event::Events, input::InputPlugin, prelude::*};
+
+    use crate::{ActionEvent, ActionPlugin, ActionSystem};
+
+    #[derive(Default)]
+    struct Snapshots(Vec<ActionSnapshot<String>>);
+
+    // Restores the next snapshot every frame, as received from the network
+    fn receive(mut snapshots: ResMut<Snapshots>, mut input_map: ResMut<InputMap<String>>) {
+        if !snapshots.0.is_empty() {
+            let snapshot = snapshots.0.remove(0);
+            input_map.restore(&snapshot);
+        }
+    }
+
+    let mut local = InputMap::<String>::default();
+    local.bind("jump", KeyCode::Space);
+    let mut input = Input::<KeyCode>::default();
+    let mut snapshots = vec![];
+    for pressed in [true, true, false] {
+        local.state.clear_just_active_inactive();
+        input.clear();
+        if pressed {
+            input.press(KeyCode::Space);
+        } else {
+            input.release(KeyCode::Space);
+        }
+        local.update_keys(&input, &default());
+        local.update_active(&input, &default(), Duration::ZERO);
+        snapshots.push(local.snapshot());
+    }
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin::<String>::default().with_events())
+        .insert_resource(Snapshots(snapshots))
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            receive.before(ActionSystem::ResolveConflicts),
+        );
+    app.world
+        .resource_mut::<InputMap<String>>()
+        .set_live_input(false);
+    let mut reader = app
+        .world
+        .resource::<Events<ActionEvent<String>>>()
+        .get_reader();
+    let mut frames = vec![];
+    for _ in 0..4 {
+        app.update();
+        let map = app.world.resource::<InputMap<String>>();
+        let events = app.world.resource::<Events<ActionEvent<String>>>();
+        frames.push((
+            map.just_active("jump"),
+            map.active("jump"),
+            map.just_inactive("jump"),
+            reader
+                .iter(events)
+                .map(|v| match v {
+                    ActionEvent::Started { .. } => "started",
+                    ActionEvent::Ongoing { .. } => "ongoing",
+                    ActionEvent::Completed { .. } => "completed",
+                    ActionEvent::Canceled { .. } => "canceled",
+                })
+                .collect::<Vec<_>>(),
+        ));
+    }
+    assert_eq!(
+        frames,
+        vec![
+            (true, true, false, vec!["started"]),
+            (false, true, false, vec!["ongoing"]),
+            (false, false, true, vec!["completed"]),
+            (false, false, false, vec![]),
+        ]
+    );
+}
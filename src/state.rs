@@ -0,0 +1,185 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    mem,
+};
+
+use bevy::ecs::component::{Component, TableStorage};
+
+/// The triggered actions of an [`crate::InputMap`], generic over the application's action type.
+///
+/// Add it to an entity alongside an [`crate::InputMap`] component to have it kept in sync with
+/// that entity's bindings, then read it with `Query<&ActionState<T>>`.
+///
+/// Systems running on a fixed timestep should read [`ActionState::fixed_just_active`] and
+/// [`ActionState::fixed_just_inactive`] instead, which hold the transitions accumulated since the
+/// previous fixed tick. See [`crate::tick_fixed_actions`].
+#[derive(Clone, Debug)]
+pub struct ActionState<T> {
+    pub(crate) active: HashMap<T, f32>,
+    pub(crate) just_active: HashMap<T, f32>,
+    pub(crate) just_inactive: HashSet<T>,
+    pending_just_active: HashMap<T, f32>,
+    pending_just_inactive: HashSet<T>,
+    fixed_just_active: HashMap<T, f32>,
+    fixed_just_inactive: HashSet<T>,
+}
+
+impl<T> Default for ActionState<T> {
+    fn default() -> Self {
+        Self {
+            active: HashMap::new(),
+            just_active: HashMap::new(),
+            just_inactive: HashSet::new(),
+            pending_just_active: HashMap::new(),
+            pending_just_inactive: HashSet::new(),
+            fixed_just_active: HashMap::new(),
+            fixed_just_inactive: HashSet::new(),
+        }
+    }
+}
+
+impl<T> Component for ActionState<T>
+where
+    T: 'static + Send + Sync,
+{
+    type Storage = TableStorage;
+}
+
+impl<T> ActionState<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Returns whether a given action is currently triggered.
+    pub fn active<K: Into<T>>(&self, key: K) -> bool {
+        self.active.contains_key(&key.into())
+    }
+
+    /// Returns whether a given action has just been triggered.
+    pub fn just_active<K: Into<T>>(&self, key: K) -> bool {
+        self.just_active.contains_key(&key.into())
+    }
+
+    /// Returns whether a given action has just stopped being triggered.
+    pub fn just_inactive<K: Into<T>>(&self, key: K) -> bool {
+        self.just_inactive.contains(&key.into())
+    }
+
+    /// Returns the strength of an active triggered action for use with analog input.
+    pub fn strength<K: Into<T>>(&self, key: K) -> f32 {
+        if let Some(strength) = self.active.get(&key.into()) {
+            *strength
+        } else {
+            0.
+        }
+    }
+
+    /// Returns whether a given action has been triggered since the previous fixed tick.
+    pub fn fixed_just_active<K: Into<T>>(&self, key: K) -> bool {
+        self.fixed_just_active.contains_key(&key.into())
+    }
+
+    /// Returns whether a given action has stopped being triggered since the previous fixed tick.
+    pub fn fixed_just_inactive<K: Into<T>>(&self, key: K) -> bool {
+        self.fixed_just_inactive.contains(&key.into())
+    }
+
+    /// Starts a new fixed tick, exposing the transitions accumulated since the previous one through
+    /// [`ActionState::fixed_just_active`] and [`ActionState::fixed_just_inactive`].
+    pub fn tick_fixed(&mut self) {
+        self.fixed_just_active = mem::take(&mut self.pending_just_active);
+        self.fixed_just_inactive = mem::take(&mut self.pending_just_inactive);
+    }
+
+    /// Records this frame's transitions for the next fixed tick
+    pub(crate) fn accumulate_fixed(&mut self) {
+        self.pending_just_active.extend(
+            self.just_active
+                .iter()
+                .map(|(k, strength)| (k.clone(), *strength)),
+        );
+        self.pending_just_inactive
+            .extend(self.just_inactive.iter().cloned());
+    }
+
+    /// Ends an active action, returning its strength if it was active
+    pub(crate) fn cancel(&mut self, action: &T) -> Option<f32> {
+        let strength = self.active.remove(action)?;
+        self.just_inactive.insert(action.clone());
+        self.pending_just_inactive.insert(action.clone());
+        Some(strength)
+    }
+
+    /// Clears the maps of just active or just inactive actions
+    pub(crate) fn clear_just_active_inactive(&mut self) {
+        self.just_active.clear();
+        self.just_inactive.clear();
+    }
+
+    /// Clears all triggered actions.
+    pub(crate) fn clear(&mut self) {
+        self.active.clear();
+        self.just_active.clear();
+        self.just_inactive.clear();
+        self.pending_just_active.clear();
+        self.pending_just_inactive.clear();
+        self.fixed_just_active.clear();
+        self.fixed_just_inactive.clear();
+    }
+}
+
+#[test]
+fn test_fixed_ticks() {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    use crate::InputMap;
+
+    /// Runs a frame with the given number of fixed ticks, returning whether jump was just active
+    /// and just inactive on each
+    fn update(
+        map: &mut InputMap<String>,
+        input: &mut Input<KeyCode>,
+        ticks: usize,
+    ) -> Vec<(bool, bool)> {
+        map.state.clear_just_active_inactive();
+        map.update_keys(input, &default());
+        map.update_active(input, &default(), Duration::ZERO);
+        input.clear();
+        (0..ticks)
+            .map(|_| {
+                map.tick_fixed();
+                (
+                    map.fixed_just_active("jump"),
+                    map.fixed_just_inactive("jump"),
+                )
+            })
+            .collect()
+    }
+
+    let mut map = InputMap::<String>::default();
+    map.bind("jump", KeyCode::Space);
+    let mut input = Input::<KeyCode>::default();
+    // Pressed in a frame without a fixed tick, then seen exactly once
+    input.press(KeyCode::Space);
+    assert!(update(&mut map, &mut input, 0).is_empty());
+    assert_eq!(
+        update(&mut map, &mut input, 2),
+        vec![(true, false), (false, false)]
+    );
+
+    // Released and pressed again between ticks
+    input.release(KeyCode::Space);
+    update(&mut map, &mut input, 0);
+    input.press(KeyCode::Space);
+    assert_eq!(update(&mut map, &mut input, 1), vec![(true, true)]);
+
+    // Canceled outside of the update, eg. by text entry
+    map.set_text_entry(true);
+    assert!(map.just_inactive("jump"));
+    assert_eq!(
+        update(&mut map, &mut input, 2),
+        vec![(false, true), (false, false)]
+    );
+}
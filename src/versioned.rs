@@ -0,0 +1,552 @@
+use std::{collections::BTreeMap, fmt, hash::Hash, marker::PhantomData};
+
+use bevy::{log::warn, prelude::default};
+use serde::{
+    de::{self, value::StrDeserializer, DeserializeOwned, MapAccess, Visitor},
+    ser::{self, Impossible},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    lenient::{BindingText, Known},
+    Action, Binding, BindingOverrides, Rejection, RejectionReason,
+};
+
+/// Bindings as saved to disk by a [`SaveFormat`], with actions identified by name so that files
+/// naming actions that no longer exist can still be loaded. Migrations registered with
+/// [`SaveFormat::migration`] edit this before it is converted back into bindings. Files without a
+/// version are at version 0.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct VersionedBindings {
+    #[serde(default)]
+    pub version: u32,
+    pub actions: BTreeMap<String, SavedAction>,
+}
+
+impl VersionedBindings {
+    /// Moves the bindings of an action to a new name, eg. after renaming an action variant.
+    pub fn rename_action(&mut self, from: &str, to: &str) {
+        if let Some(action) = self.actions.remove(from) {
+            self.actions.insert(to.to_string(), action);
+        }
+    }
+
+    /// Removes the bindings of an action, eg. after removing an action variant.
+    pub fn remove_action(&mut self, name: &str) -> Option<SavedAction> {
+        self.actions.remove(name)
+    }
+}
+
+/// An action as saved by a [`SaveFormat`], with bindings in the string syntax of
+/// [`binding_string`](crate::binding_string) so that migrations can edit bindings that no longer
+/// deserialize, eg. after a key was renamed. Bindings saved in their struct form by earlier
+/// versions are converted to strings when deserialized.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SavedAction {
+    #[serde(default, deserialize_with = "deserialize_bindings")]
+    pub bindings: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default_priority")]
+    pub priority: i32,
+}
+
+impl SavedAction {
+    /// Keeps the bindings that parse, recording why the others were rejected
+    fn into_action(self, name: &str, rejected: &mut Vec<Rejection>) -> Action {
+        let mut action = Action {
+            priority: self.priority,
+            ..default()
+        };
+        for (i, binding) in self.bindings.iter().enumerate() {
+            match binding.parse::<Binding>() {
+                Ok(binding) => action.bindings.push(binding),
+                Err(e) => {
+                    warn!("Skipping binding {} of action {}: {}", binding, name, e);
+                    rejected.push(Rejection {
+                        action: name.to_string(),
+                        binding: Some(i),
+                        reason: e.into(),
+                    });
+                }
+            }
+        }
+        action
+    }
+}
+
+impl From<&Action> for SavedAction {
+    fn from(action: &Action) -> Self {
+        Self {
+            bindings: action.bindings.iter().map(ToString::to_string).collect(),
+            priority: action.priority,
+        }
+    }
+}
+
+fn deserialize_bindings<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    Ok(Vec::<BindingText>::deserialize(deserializer)?
+        .into_iter()
+        .map(|v| v.0)
+        .collect())
+}
+
+fn is_default_priority(priority: &i32) -> bool {
+    *priority == 0
+}
+
+/// Bindings saved before they were versioned, as a bare map of actions such as a serialized
+/// [`InputMap`](crate::InputMap). Files that fail to deserialize as [`VersionedBindings`] can be
+/// deserialized as this instead, and converted to version 0 so that every migration applies to
+/// them. Actions that no longer exist are kept by name, so that migrations can rename them.
+///
+/// ```
+/// # use bevy_input_actionmap::*;
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize)]
+/// enum Action {
+///     Leap,
+/// }
+///
+/// let format = SaveFormat::new(1).migration(0, |file| file.rename_action("Jump", "Leap"));
+/// let saved = "{Jump: (bindings: [(keys: [Space], gamepad_buttons: [])])}";
+/// let saved = ron::from_str::<VersionedBindings>(saved)
+///     .or_else(|_| ron::from_str::<LegacyBindings<Action>>(saved).map(Into::into))
+///     .unwrap();
+/// let (overrides, rejected) = format.load::<Action>(saved);
+/// let (action, bindings) = overrides.iter().next().unwrap();
+/// assert_eq!((action, bindings.bindings().len()), (&Action::Leap, 1));
+/// assert!(rejected.is_empty());
+/// ```
+pub struct LegacyBindings<T> {
+    actions: BTreeMap<String, SavedAction>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> From<LegacyBindings<T>> for VersionedBindings {
+    fn from(legacy: LegacyBindings<T>) -> Self {
+        VersionedBindings {
+            version: 0,
+            actions: legacy.actions,
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for LegacyBindings<T>
+where
+    T: Deserialize<'de> + Serialize,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(LegacyBindingsVisitor(PhantomData))
+    }
+}
+
+struct LegacyBindingsVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> Visitor<'de> for LegacyBindingsVisitor<T>
+where
+    T: Deserialize<'de> + Serialize,
+{
+    type Value = LegacyBindings<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Expected a valid Input mapping")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut actions = BTreeMap::new();
+        while let Some((key, action)) = access.next_entry::<Known<T>, SavedAction>()? {
+            let name = match key.0 {
+                Ok(key) => key.serialize(NameSerializer).map_err(de::Error::custom)?,
+                Err(name) => name,
+            };
+            actions.insert(name, action);
+        }
+        Ok(LegacyBindings {
+            actions,
+            marker: PhantomData,
+        })
+    }
+}
+
+type Migration = Box<dyn Fn(&mut VersionedBindings) + Send + Sync>;
+
+/// A versioned save format for bindings, upgrading files saved by earlier versions of the
+/// application with registered migrations.
+///
+/// ```
+/// # use bevy_input_actionmap::*;
+/// #[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize)]
+/// enum Action {
+///     Leap,
+/// }
+///
+/// // Version 2 renamed `Jump` to `Leap`
+/// let format = SaveFormat::new(2).migration(1, |file| file.rename_action("Jump", "Leap"));
+/// let saved = r#"(version: 1, actions: {"Jump": (bindings: []), "Dance": (bindings: [])})"#;
+/// let saved = ron::from_str::<VersionedBindings>(saved).unwrap();
+/// let (overrides, rejected) = format.load::<Action>(saved);
+/// assert_eq!(overrides.iter().count(), 1);
+/// assert_eq!(rejected[0].action, "Dance");
+/// ```
+pub struct SaveFormat {
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+}
+
+impl SaveFormat {
+    /// Creates a format whose files are saved with the given version.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a function upgrading files saved with version `from` to version `from + 1`.
+    /// Versions without a migration are upgraded unchanged.
+    pub fn migration<F>(mut self, from: u32, migration: F) -> Self
+    where
+        F: Fn(&mut VersionedBindings) + Send + Sync + 'static,
+    {
+        self.migrations.insert(from, Box::new(migration));
+        self
+    }
+
+    /// Converts overrides into their saved form at the current version. Actions whose name can't
+    /// be represented as a string are skipped with a warning.
+    pub fn save<T: Serialize + Eq + Hash>(
+        &self,
+        overrides: &BindingOverrides<T>,
+    ) -> VersionedBindings {
+        let mut actions = BTreeMap::new();
+        for (k, v) in overrides.iter() {
+            match k.serialize(NameSerializer) {
+                Ok(name) => {
+                    actions.insert(name, v.into());
+                }
+                Err(e) => warn!("Not saving bindings of action: {}", e),
+            }
+        }
+        VersionedBindings {
+            version: self.version,
+            actions,
+        }
+    }
+
+    /// Upgrades saved bindings to the current version and converts them into overrides. Actions
+    /// whose name doesn't match one of the application's actions, and bindings that don't parse,
+    /// are skipped and returned alongside the overrides.
+    pub fn load<T>(&self, mut saved: VersionedBindings) -> (BindingOverrides<T>, Vec<Rejection>)
+    where
+        T: DeserializeOwned + Eq + Hash,
+    {
+        if saved.version > self.version {
+            warn!(
+                "Bindings were saved with version {}, newer than {}",
+                saved.version, self.version
+            );
+        }
+        while saved.version < self.version {
+            if let Some(migration) = self.migrations.get(&saved.version) {
+                migration(&mut saved);
+            }
+            saved.version += 1;
+        }
+        let mut overrides = BindingOverrides::default();
+        let mut rejected = vec![];
+        for (name, action) in saved.actions {
+            match T::deserialize(StrDeserializer::<serde::de::value::Error>::new(&name)) {
+                Ok(k) => {
+                    let action = action.into_action(&name, &mut rejected);
+                    overrides.actions.insert(k, action);
+                }
+                Err(_) => {
+                    warn!("Skipping bindings of unknown action {}", name);
+                    rejected.push(Rejection {
+                        action: name,
+                        binding: None,
+                        reason: RejectionReason::UnknownAction,
+                    });
+                }
+            }
+        }
+        (overrides, rejected)
+    }
+}
+
+/// Error returned when an action can't be named by [`NameSerializer`]
+#[derive(Debug)]
+pub(crate) struct NameError(String);
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NameError {}
+
+impl ser::Error for NameError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        NameError(msg.to_string())
+    }
+}
+
+/// Serializes strings and unit enum variants, such as typical action types, to their name
+pub(crate) struct NameSerializer;
+
+impl NameSerializer {
+    fn unsupported<T>(kind: &str) -> Result<T, NameError> {
+        Err(NameError(format!(
+            "{} can't be used as an action name",
+            kind
+        )))
+    }
+}
+
+impl Serializer for NameSerializer {
+    type Ok = String;
+    type Error = NameError;
+    type SerializeSeq = Impossible<String, NameError>;
+    type SerializeTuple = Impossible<String, NameError>;
+    type SerializeTupleStruct = Impossible<String, NameError>;
+    type SerializeTupleVariant = Impossible<String, NameError>;
+    type SerializeMap = Impossible<String, NameError>;
+    type SerializeStruct = Impossible<String, NameError>;
+    type SerializeStructVariant = Impossible<String, NameError>;
+
+    fn serialize_str(self, v: &str) -> Result<String, NameError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, NameError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, NameError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String, NameError> {
+        Self::unsupported("bool")
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<String, NameError> {
+        Self::unsupported("i8")
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<String, NameError> {
+        Self::unsupported("i16")
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<String, NameError> {
+        Self::unsupported("i32")
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<String, NameError> {
+        Self::unsupported("i64")
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<String, NameError> {
+        Self::unsupported("u8")
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<String, NameError> {
+        Self::unsupported("u16")
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<String, NameError> {
+        Self::unsupported("u32")
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<String, NameError> {
+        Self::unsupported("u64")
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, NameError> {
+        Self::unsupported("f32")
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, NameError> {
+        Self::unsupported("f64")
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, NameError> {
+        Self::unsupported("bytes")
+    }
+
+    fn serialize_none(self) -> Result<String, NameError> {
+        Self::unsupported("Option")
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String, NameError> {
+        Self::unsupported("Option")
+    }
+
+    fn serialize_unit(self) -> Result<String, NameError> {
+        Self::unsupported("()")
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _value: &T,
+    ) -> Result<String, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NameError> {
+        Self::unsupported("sequence")
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NameError> {
+        Self::unsupported("tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NameError> {
+        Self::unsupported("map")
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NameError> {
+        Self::unsupported(name)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NameError> {
+        Self::unsupported(name)
+    }
+}
+
+#[test]
+fn test_versioned_round_trip() {
+    #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+    enum TestAction {
+        Jump,
+        Fire,
+    }
+
+    let mut map = crate::InputMap::<TestAction>::default();
+    map.bind(TestAction::Jump, bevy::prelude::KeyCode::Space)
+        .bind(TestAction::Fire, bevy::prelude::KeyCode::LControl);
+    let format = SaveFormat::new(1);
+    let saved = ron::to_string(&format.save(&map.overrides())).expect("Failed serialization");
+    let saved = ron::from_str::<VersionedBindings>(&saved).expect("Failed deserialization");
+    let (overrides, rejected) = format.load::<TestAction>(saved);
+    assert!(rejected.is_empty());
+    assert_eq!(overrides, map.overrides());
+}
+
+#[test]
+fn test_migrations() {
+    use bevy::prelude::KeyCode;
+
+    #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+    enum TestAction {
+        Left,
+        Select,
+    }
+
+    // Saved before versioning, with an action renamed and a key named differently since
+    let saved = "{
+        Left: (bindings: [(keys: [Left], gamepad_buttons: [], gamepad_axis_directions: [], deadzone: 0)]),
+        Choose: (bindings: [(keys: [Enter], gamepad_buttons: [Select], deadzone: 0.5)]),
+    }";
+    assert!(ron::from_str::<VersionedBindings>(saved).is_err());
+    let saved: VersionedBindings = ron::from_str::<LegacyBindings<TestAction>>(saved)
+        .expect("Failed deserialization")
+        .into();
+    assert_eq!(saved.version, 0);
+    assert_eq!(
+        saved.actions["Choose"].bindings,
+        vec!["Gamepad:Select+Enter > 0.5"]
+    );
+
+    let format = SaveFormat::new(1).migration(0, |file| {
+        file.rename_action("Choose", "Select");
+        for action in file.actions.values_mut() {
+            for binding in &mut action.bindings {
+                *binding = binding.replace("Enter", "Return");
+            }
+        }
+    });
+    let (overrides, rejected) = format.load::<TestAction>(saved.clone());
+    assert!(rejected.is_empty());
+    let mut map = crate::InputMap::<TestAction>::default();
+    map.apply_overrides(&overrides);
+    assert_eq!(map.bindings(TestAction::Left), vec![KeyCode::Left.into()]);
+    assert_eq!(
+        map.bindings(TestAction::Select),
+        vec!["Gamepad:Select+Return > 0.5".parse().unwrap()]
+    );
+
+    // Without the migration
+    let (overrides, rejected) = SaveFormat::new(0).load::<TestAction>(saved);
+    assert_eq!(overrides.iter().count(), 1);
+    assert_eq!(rejected.len(), 1);
+
+    let saved = r#"(actions: {"Left": (bindings: ["Spacebar", "A"])})"#;
+    let saved = ron::from_str::<VersionedBindings>(saved).expect("Failed deserialization");
+    assert_eq!(saved.version, 0);
+    let (overrides, rejected) = format.load::<TestAction>(saved);
+    assert_eq!(overrides.iter().next().unwrap().1.bindings().len(), 1);
+    assert_eq!(
+        rejected,
+        vec![Rejection {
+            action: "Left".to_string(),
+            binding: Some(0),
+            reason: RejectionReason::UnknownInput("Spacebar".to_string()),
+        }]
+    );
+}
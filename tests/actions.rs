@@ -0,0 +1,263 @@
+use bevy::{
+    ecs::event::{Events, ManualEventReader},
+    input::InputPlugin,
+    prelude::*,
+};
+use bevy_input_actionmap::*;
+
+/// A headless app running the [`ActionPlugin`], fed with input one frame at a time. Keys and mouse
+/// buttons go through Bevy's `InputPlugin` as keyboard and mouse events, and gamepad events are
+/// sent as Bevy sends them once its gamepad settings are applied.
+struct Harness {
+    app: App,
+    events: ManualEventReader<ActionEvent<String>>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_plugin(ActionPlugin::<String>::default().with_events());
+        Self {
+            app,
+            events: default(),
+        }
+    }
+
+    fn map(&mut self) -> Mut<'_, InputMap<String>> {
+        self.app.world.resource_mut::<InputMap<String>>()
+    }
+
+    fn press(&mut self, key: KeyCode) -> &mut Self {
+        self.app.press_key(key);
+        self
+    }
+
+    fn release(&mut self, key: KeyCode) -> &mut Self {
+        self.app.release_key(key);
+        self
+    }
+
+    fn click(&mut self, button: MouseButton) -> &mut Self {
+        self.app.press_mouse_button(button);
+        self
+    }
+
+    fn gamepad(&mut self, event: GamepadEventType) -> &mut Self {
+        self.app
+            .world
+            .resource_mut::<Events<GamepadEvent>>()
+            .send(GamepadEvent(Gamepad(0), event));
+        self
+    }
+
+    /// Runs a frame with the input fed since the last one, returning the events sent
+    fn frame(&mut self) -> Vec<ActionEvent<String>> {
+        self.app.update();
+        let events = self.app.world.resource::<Events<ActionEvent<String>>>();
+        self.events.iter(events).cloned().collect()
+    }
+
+    fn active(&self, action: &str) -> bool {
+        self.app.world.resource::<InputMap<String>>().active(action)
+    }
+
+    fn just_active(&self, action: &str) -> bool {
+        self.app
+            .world
+            .resource::<InputMap<String>>()
+            .just_active(action)
+    }
+
+    fn just_inactive(&self, action: &str) -> bool {
+        self.app
+            .world
+            .resource::<InputMap<String>>()
+            .just_inactive(action)
+    }
+
+    fn strength(&self, action: &str) -> f32 {
+        self.app
+            .world
+            .resource::<InputMap<String>>()
+            .strength(action)
+    }
+}
+
+fn started(events: &[ActionEvent<String>], name: &str) -> bool {
+    events
+        .iter()
+        .any(|v| matches!(v, ActionEvent::Started { action, .. } if action == name))
+}
+
+fn canceled(events: &[ActionEvent<String>], name: &str) -> bool {
+    events
+        .iter()
+        .any(|v| matches!(v, ActionEvent::Canceled { action, .. } if action == name))
+}
+
+fn completed(events: &[ActionEvent<String>], name: &str) -> bool {
+    events
+        .iter()
+        .any(|v| matches!(v, ActionEvent::Completed { action, .. } if action == name))
+}
+
+#[test]
+fn chords() {
+    let mut h = Harness::new();
+    h.map()
+        .bind("select_all", vec![KeyCode::LControl, KeyCode::A]);
+    h.press(KeyCode::LControl).frame();
+    assert!(!h.active("select_all"));
+    let events = h.press(KeyCode::A).frame();
+    assert!(h.just_active("select_all"));
+    assert!(started(&events, "select_all"));
+    h.frame();
+    assert!(h.active("select_all") && !h.just_active("select_all"));
+    let events = h.release(KeyCode::LControl).frame();
+    assert!(h.just_inactive("select_all"));
+    assert!(completed(&events, "select_all"));
+
+    let mut h = Harness::new();
+    h.map().bind(
+        "alt_fire",
+        Binding::from(MouseButton::Left).with_modifier(Modifier::Shift),
+    );
+    h.click(MouseButton::Left).frame();
+    assert!(!h.active("alt_fire"));
+    h.press(KeyCode::RShift).frame();
+    assert!(h.just_active("alt_fire"));
+}
+
+#[test]
+fn deadzones() {
+    let mut h = Harness::new();
+    h.map()
+        .bind_with_deadzone("steer", GamepadAxisDirection::LeftStickXPositive, 0.25)
+        .bind_with_deadzone("accelerate", GamepadButtonType::RightTrigger2, 0.1);
+    h.gamepad(GamepadEventType::Connected).frame();
+    h.gamepad(GamepadEventType::AxisChanged(
+        GamepadAxisType::LeftStickX,
+        0.2,
+    ))
+    .gamepad(GamepadEventType::ButtonChanged(
+        GamepadButtonType::RightTrigger2,
+        0.05,
+    ))
+    .frame();
+    assert!(!h.active("steer"));
+    assert!(!h.active("accelerate"));
+    h.gamepad(GamepadEventType::AxisChanged(
+        GamepadAxisType::LeftStickX,
+        0.5,
+    ))
+    .gamepad(GamepadEventType::ButtonChanged(
+        GamepadButtonType::RightTrigger2,
+        0.8,
+    ))
+    .frame();
+    assert!(h.just_active("steer"));
+    assert_eq!(h.strength("steer"), 0.5);
+    assert_eq!(h.strength("accelerate"), 0.8);
+    h.gamepad(GamepadEventType::AxisChanged(
+        GamepadAxisType::LeftStickX,
+        -0.5,
+    ))
+    .frame();
+    assert!(h.just_inactive("steer"));
+}
+
+#[test]
+fn conflict_pruning() {
+    let mut h = Harness::new();
+    h.map()
+        .bind("newline", KeyCode::Return)
+        .bind("submit", vec![KeyCode::LControl, KeyCode::Return])
+        .bind(
+            "submit_all",
+            vec![KeyCode::LControl, KeyCode::LAlt, KeyCode::Return],
+        );
+    h.press(KeyCode::LControl)
+        .press(KeyCode::LAlt)
+        .press(KeyCode::Return)
+        .frame();
+    assert!(h.active("submit_all"));
+    assert!(!h.active("submit"));
+    assert!(!h.active("newline"));
+    // The next most specific binding takes over once a key is released
+    let events = h.release(KeyCode::LAlt).frame();
+    assert!(h.just_inactive("submit_all"));
+    assert!(h.active("submit") && !h.just_active("submit"));
+    assert!(started(&events, "submit"));
+
+    // Partially overlapping chords suppress each other
+    let mut h = Harness::new();
+    h.map()
+        .bind("save", vec![KeyCode::LControl, KeyCode::S])
+        .bind("sprint", vec![KeyCode::LShift, KeyCode::S]);
+    h.press(KeyCode::S).press(KeyCode::LControl).frame();
+    assert!(h.active("save"));
+    let events = h.press(KeyCode::LShift).frame();
+    assert!(!h.active("save") && !h.active("sprint"));
+    assert!(canceled(&events, "save"));
+}
+
+#[test]
+fn transitions() {
+    let mut h = Harness::new();
+    h.map().bind("jump", KeyCode::Space);
+    let events = h.press(KeyCode::Space).frame();
+    assert!(h.just_active("jump") && h.active("jump"));
+    assert!(started(&events, "jump"));
+    h.frame();
+    assert!(!h.just_active("jump") && h.active("jump"));
+    let events = h.release(KeyCode::Space).frame();
+    assert!(h.just_inactive("jump") && !h.active("jump"));
+    assert!(completed(&events, "jump"));
+    h.frame();
+    assert!(!h.just_inactive("jump"));
+
+    // Released and pressed again in the next frame
+    h.press(KeyCode::Space).frame();
+    h.release(KeyCode::Space).frame();
+    h.press(KeyCode::Space).frame();
+    assert!(h.just_active("jump"));
+
+    // Tapped within a single frame
+    let mut h = Harness::new();
+    h.map().bind("jump", KeyCode::Space);
+    h.press(KeyCode::Space).release(KeyCode::Space).frame();
+    assert!(!h.active("jump") && !h.just_active("jump"));
+}
+
+#[test]
+fn clear() {
+    let mut h = Harness::new();
+    h.map().bind("jump", KeyCode::Space);
+    h.press(KeyCode::Space).frame();
+    assert!(h.active("jump"));
+    h.map().clear();
+    assert!(!h.active("jump"));
+    let events = h.frame();
+    assert!(canceled(&events, "jump"));
+    // The held key no longer counts until it is pressed again
+    h.frame();
+    assert!(!h.active("jump"));
+    h.release(KeyCode::Space).frame();
+    h.press(KeyCode::Space).frame();
+    assert!(h.just_active("jump"));
+
+    // Likewise for physical keys
+    let mut h = Harness::new();
+    h.map().bind("up", Binding::physical(KeyCode::W));
+    h.press(KeyCode::W).frame();
+    assert!(h.active("up"));
+    h.map().clear();
+    h.frame();
+    h.frame();
+    assert!(!h.active("up"));
+    h.release(KeyCode::W).frame();
+    h.press(KeyCode::W).frame();
+    assert!(h.just_active("up"));
+}